@@ -0,0 +1,14 @@
+// Shared by every object of a frame (FrameUniforms), MAX_LIGHTS is defined by the renderer
+
+struct Light {
+    vec4 direction;
+    vec4 color;
+};
+
+layout(binding = 0) uniform Frame {
+    mat4 view;
+    mat4 proj;
+    vec4 ambient;
+    Light lights[MAX_LIGHTS];
+    uint lightCount;
+} frame;
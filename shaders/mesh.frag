@@ -0,0 +1,24 @@
+#version 450
+
+#include "frame.glsl"
+#include "object.glsl"
+
+// The material's base color texture (a white texel when it has none)
+layout(set = 1, binding = 0) uniform sampler2D baseColorTexture;
+
+layout(location = 0) in vec3 fragColor;
+layout(location = 1) in vec3 fragNormal;
+layout(location = 2) in vec2 fragUV;
+
+layout(location = 0) out vec4 outColor;
+
+void main() {
+    vec3 albedo = fragColor * object.baseColor.rgb * texture(baseColorTexture, fragUV).rgb;
+    vec3 normal = normalize(fragNormal);
+    vec3 light = frame.ambient.rgb;
+    for (uint i = 0u; i < frame.lightCount; i++) {
+        float diffuse = max(dot(normal, frame.lights[i].direction.xyz), 0.0);
+        light += frame.lights[i].color.rgb * diffuse;
+    }
+    outColor = vec4(albedo * light, 1.0);
+}
@@ -0,0 +1,20 @@
+#version 450
+
+#include "frame.glsl"
+#include "object.glsl"
+
+layout(location = 0) in vec3 inPosition;
+layout(location = 1) in vec3 inColor;
+layout(location = 2) in vec3 inNormal;
+layout(location = 3) in vec2 inUV;
+
+layout(location = 0) out vec3 fragColor;
+layout(location = 1) out vec3 fragNormal;
+layout(location = 2) out vec2 fragUV;
+
+void main() {
+    gl_Position = frame.proj * frame.view * object.model * vec4(inPosition, 1.0);
+    fragColor = inColor;
+    fragNormal = mat3(object.model) * inNormal;
+    fragUV = inUV;
+}
@@ -0,0 +1,6 @@
+// Pushed for each object drawn (ObjectConstants)
+
+layout(push_constant) uniform Object {
+    mat4 model;
+    vec4 baseColor;
+} object;
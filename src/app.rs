@@ -1,46 +1,101 @@
+mod file_watcher;
 mod graphics_engine;
+mod input;
 mod instance;
 mod model;
+#[cfg(test)]
+mod temp_dir;
 
-use ash::vk::SurfaceKHR;
-use graphics_engine::GraphicsEngine;
+use std::{
+    error::Error,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use ash::vk::{Extent2D, SurfaceKHR};
+use file_watcher::FileWatcher;
+use graphics_engine::{save_png, GraphicsEngine, HeadlessEngine, ToMesh, DEFAULT_FRAMES_IN_FLIGHT};
+use input::{Action, Bindings, Input};
 use instance::Instance;
-use model::Model;
+use model::{
+    scene_file::{MeshFiles, SceneError},
+    Model,
+};
 use winit::{
     application::ApplicationHandler,
     dpi::PhysicalSize,
-    event::WindowEvent,
+    event::{DeviceEvent, DeviceId, WindowEvent},
     event_loop::ActiveEventLoop,
     raw_window_handle::{HasDisplayHandle, HasWindowHandle, RawDisplayHandle},
-    window::{Window, WindowId},
+    window::{CursorGrabMode, Window, WindowId},
 };
 
 const TITLE: &str = "Real Engine";
 const WIDTH: u32 = 600;
 const HEIGHT: u32 = 600;
+const FRAMES_IN_FLIGHT: usize = DEFAULT_FRAMES_IN_FLIGHT;
+// Where the scene is saved when it wasn't loaded from a file
+const DEFAULT_SCENE_PATH: &str = "scene.ron";
+// How often the scene and shader files are checked for changes
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
 
 pub struct App {
     instance: Instance,
     model: Model,
-    // Rendering
+    // The scene file the model was loaded from (saved to, reloaded when changed),
+    // and the files added to it
+    scene_path: Option<PathBuf>,
+    file_paths: Vec<PathBuf>,
+    scene_watcher: FileWatcher,
+    // Mesh files of the model, reused by reloads when unchanged
+    mesh_files: MeshFiles,
+    input: Input,
+    // Rendering, the pipeline rebuilt when its shaders change
     window: Option<Window>,
     graphics_engine: Option<GraphicsEngine>,
+    shader_watcher: FileWatcher,
 }
 
 impl App {
-    pub fn new(raw_display_handle: RawDisplayHandle) -> App {
-        App {
+    // "scene_path" : scene file to show instead of the showcase,
+    // "file_paths" : OBJ and glTF files to show along with it
+    pub fn new(
+        raw_display_handle: RawDisplayHandle,
+        scene_path: Option<PathBuf>,
+        file_paths: &[PathBuf],
+    ) -> Result<App, SceneError> {
+        let mut mesh_files = MeshFiles::new();
+        let model = create_model(scene_path.as_deref(), file_paths, &mut mesh_files)?;
+        let mut scene_watcher = FileWatcher::new(POLL_INTERVAL);
+        if let Some(path) = &scene_path {
+            scene_watcher.watch(path);
+        }
+        Ok(App {
             instance: Instance::new(raw_display_handle),
-            model: Model::new(),
+            model,
+            scene_path,
+            file_paths: file_paths.to_vec(),
+            scene_watcher,
+            mesh_files,
+            input: Input::new(Bindings::default_bindings()),
             window: None,
             graphics_engine: None,
-        }
+            shader_watcher: FileWatcher::new(POLL_INTERVAL),
+        })
     }
 
     pub fn setup_rendering(&mut self, event_loop: &ActiveEventLoop) {
         let window = create_window(event_loop);
         let surface = create_surface(&self.instance, &window);
-        let graphics_engine = GraphicsEngine::new(&self.instance, surface);
+        let graphics_engine = GraphicsEngine::new(
+            &self.instance,
+            surface,
+            to_extent(window.inner_size()),
+            FRAMES_IN_FLIGHT,
+        );
+        for path in graphics_engine.shader_files() {
+            self.shader_watcher.watch(&path);
+        }
         self.window = Some(window);
         self.graphics_engine = Some(graphics_engine);
     }
@@ -55,16 +110,202 @@ impl App {
     }
 
     pub fn redraw(&mut self) {
-        self.model.step_if_enough_time();
+        if !self.scene_watcher.poll().is_empty() {
+            self.reload_scene();
+        }
+        if !self.shader_watcher.poll().is_empty() {
+            self.reload_shaders();
+        }
+        self.model.update(&self.input);
+        self.handle_actions();
+        self.input.end_step();
+        let objects = self.model.interpolated_objects();
+        let graphics_engine = self.graphics_engine.as_mut().unwrap();
+        graphics_engine.frame(
+            objects.iter().map(|object| object as &dyn ToMesh).collect(),
+            self.model.camera(),
+            self.model.lighting(),
+        );
+        let paused = graphics_engine.is_paused();
+        if let Some((path, outcome)) = graphics_engine.take_screenshot_outcome() {
+            match outcome {
+                Ok(()) => self.report(&format!("Screenshot saved to {}", path.display())),
+                Err(err) => self.report(&format!("Failed to save screenshot : {err}")),
+            }
+        }
+        // Request "Redraw" again, making it loop as fast as possible (until minimized)
+        if !paused {
+            self.window.as_ref().unwrap().request_redraw();
+        }
+    }
+
+    // Outcome of what the user asked for, shown in the window title until the next one
+    fn report(&self, status: &str) {
+        if let Some(window) = &self.window {
+            window.set_title(&format!("{TITLE} - {status}"));
+        }
+    }
+
+    // Actions handled by the app rather than the model, and the model's changes to report
+    fn handle_actions(&mut self) {
+        if self.input.is_action_just_pressed(Action::SwitchCamera) {
+            let name = self.model.camera_controller().name();
+            self.report(&format!("Camera : {name}"));
+        }
+        if self.input.is_action_just_pressed(Action::SwitchProjection) {
+            let name = self.model.camera().projection.name();
+            self.report(&format!("Projection : {name}"));
+        }
+        if self.input.is_action_just_pressed(Action::Screenshot) {
+            self.screenshot();
+        }
+        if self.input.is_action_just_pressed(Action::ToggleCursorGrab) {
+            self.grab_cursor(!self.input.is_cursor_grabbed());
+        }
+        if self.input.is_action_just_pressed(Action::SaveScene) {
+            self.save_scene();
+        }
+    }
+
+    // To the file it was loaded from, DEFAULT_SCENE_PATH otherwise
+    pub fn save_scene(&mut self) {
+        let path = self
+            .scene_path
+            .get_or_insert_with(|| PathBuf::from(DEFAULT_SCENE_PATH))
+            .clone();
+        match self.model.save_scene(&path) {
+            Ok(()) => self.report(&format!("Scene saved to {}", path.display())),
+            Err(err) => self.report(&format!("Failed to save the scene : {err}")),
+        }
+        // Watched from now on, but what was just written isn't an edit
+        self.scene_watcher.watch(&path);
+        self.scene_watcher.mark_seen();
+    }
+
+    // The scene file changed : rebuild the model from it, keeping the camera, and drop the
+    // geometries and textures it doesn't draw anymore (mesh files unchanged since keep theirs).
+    // A scene that fails to load (maybe still being written) is reported and the model kept
+    pub fn reload_scene(&mut self) {
+        let path = self.scene_path.as_deref();
+        match create_model(path, &self.file_paths, &mut self.mesh_files) {
+            Ok(model) => {
+                let previous = std::mem::replace(&mut self.model, model);
+                if let Some(graphics_engine) = self.graphics_engine.as_mut() {
+                    let kept = self.model.mesh_keys();
+                    for key in previous.mesh_keys().difference(&kept) {
+                        graphics_engine.invalidate_mesh(key);
+                    }
+                    let kept = self.model.texture_keys();
+                    for key in previous.texture_keys().difference(&kept) {
+                        graphics_engine.invalidate_texture(key);
+                    }
+                }
+                self.model.keep_view_of(previous);
+                self.report("Scene reloaded");
+            }
+            Err(err) => self.report(&format!("Failed to reload the scene : {err}")),
+        }
+    }
+
+    // Shader files changed : rebuild the pipeline from them. Shaders that fail to compile
+    // are reported (their diagnostics printed) and the pipeline kept
+    pub fn reload_shaders(&mut self) {
+        let graphics_engine = self.graphics_engine.as_mut().unwrap();
+        let result = graphics_engine.reload_shaders();
+        // Files added since (new includes)
+        for path in graphics_engine.shader_files() {
+            self.shader_watcher.watch(&path);
+        }
+        match result {
+            Ok(()) => self.report("Shaders reloaded"),
+            Err(err) => {
+                eprintln!("Failed to reload shaders :\n{err}");
+                self.report("Failed to reload shaders (see the terminal)");
+            }
+        }
+    }
+
+    // Hidden and locked in place (or kept inside the window where locking isn't supported)
+    pub fn grab_cursor(&mut self, grabbed: bool) {
+        let window = self.window.as_ref().unwrap();
+        let result = if grabbed {
+            window
+                .set_cursor_grab(CursorGrabMode::Locked)
+                .or_else(|_| window.set_cursor_grab(CursorGrabMode::Confined))
+        } else {
+            window.set_cursor_grab(CursorGrabMode::None)
+        };
+        match result {
+            Ok(()) => {
+                window.set_cursor_visible(!grabbed);
+                self.input.set_cursor_grabbed(grabbed);
+            }
+            Err(err) => eprintln!("Failed to grab the cursor : {err}"),
+        }
+    }
+
+    // Saved in the working directory, named after the time it was taken
+    pub fn screenshot(&mut self) {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_millis());
+        let path = PathBuf::from(format!("screenshot_{timestamp}.png"));
+        // Taken on the next redraw
+        self.graphics_engine
+            .as_mut()
+            .unwrap()
+            .request_screenshot(path);
+    }
+
+    pub fn resize(&mut self, size: PhysicalSize<u32>) {
         self.graphics_engine
             .as_mut()
             .unwrap()
-            .frame(self.model.objects_to_draw(), self.model.camera());
-        // Request "Redraw" again, making it loop as fast as possible
+            .resize(to_extent(size));
+        // Restart the loop in case it was paused
         self.window.as_ref().unwrap().request_redraw();
     }
 }
 
+// The showcase or the scene of "scene_path", with the objects of "file_paths".
+// "mesh_files" : loaded already (by a previous model)
+fn create_model(
+    scene_path: Option<&Path>,
+    file_paths: &[PathBuf],
+    mesh_files: &mut MeshFiles,
+) -> Result<Model, SceneError> {
+    let mut model = match scene_path {
+        Some(path) => Model::load_scene(path, mesh_files)?,
+        None => Model::new(),
+    };
+    model.load_files(file_paths, mesh_files);
+    Ok(model)
+}
+
+// Without window : render the model once (WIDTH x HEIGHT) and save it as a PNG to "output"
+pub fn render_headless(
+    scene_path: Option<&Path>,
+    file_paths: &[PathBuf],
+    output: &Path,
+) -> Result<(), Box<dyn Error>> {
+    let model = create_model(scene_path, file_paths, &mut MeshFiles::new())?;
+    let instance = Instance::new_headless();
+
+    let extent = to_extent(PhysicalSize::new(WIDTH, HEIGHT));
+    let mut headless_engine = HeadlessEngine::new(&instance, extent);
+    let objects = model.objects_to_draw();
+    let rgba = headless_engine.render(
+        objects.iter().map(|object| object as &dyn ToMesh).collect(),
+        model.camera(),
+        model.lighting(),
+    );
+    let extent = headless_engine.extent();
+    headless_engine.destroy();
+
+    save_png(output, extent, &rgba)?;
+    Ok(())
+}
+
 // Trait to be able to receive events from event_loop
 impl ApplicationHandler for App {
     // Called when we resume the app
@@ -73,11 +314,12 @@ impl ApplicationHandler for App {
         self.setup_rendering(event_loop);
     }
 
-    // Handles "Redraw" and "Close" requests
+    // Handles "Redraw", "Resize" and "Close" requests, the others go to input
     fn window_event(&mut self, event_loop: &ActiveEventLoop, _: WindowId, event: WindowEvent) {
         if event_loop.exiting() {
             return;
         }
+        self.input.handle_window_event(&event);
         match event {
             WindowEvent::CloseRequested => {
                 self.close();
@@ -86,9 +328,17 @@ impl ApplicationHandler for App {
             WindowEvent::RedrawRequested => {
                 self.redraw();
             }
+            WindowEvent::Resized(size) => {
+                self.resize(size);
+            }
             _ => {}
         }
     }
+
+    // Raw mouse motion goes to input
+    fn device_event(&mut self, _: &ActiveEventLoop, _: DeviceId, event: DeviceEvent) {
+        self.input.handle_device_event(&event);
+    }
 }
 
 // Create basic window with TITLE, WIDTH, HEIGHT
@@ -101,6 +351,13 @@ fn create_window(event_loop: &ActiveEventLoop) -> Window {
         .expect("Failed to create window")
 }
 
+fn to_extent(size: PhysicalSize<u32>) -> Extent2D {
+    Extent2D {
+        width: size.width,
+        height: size.height,
+    }
+}
+
 // Get inner window as a surfaceKHR
 fn create_surface(instance: &Instance, window: &Window) -> SurfaceKHR {
     unsafe {
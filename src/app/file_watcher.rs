@@ -0,0 +1,71 @@
+#[cfg(test)]
+mod tests;
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime},
+};
+
+// Files checked for changes by their modification time, at most every "interval"
+// (polling : no OS service, works the same everywhere)
+pub struct FileWatcher {
+    // Each file with its modification time when last seen (None while unreadable)
+    files: Vec<(PathBuf, Option<SystemTime>)>,
+    interval: Duration,
+    last_poll: Option<Instant>,
+}
+
+impl FileWatcher {
+    pub fn new(interval: Duration) -> FileWatcher {
+        FileWatcher {
+            files: Vec::new(),
+            interval,
+            last_poll: None,
+        }
+    }
+
+    // Changes are those made after this
+    pub fn watch(&mut self, path: &Path) {
+        if self.files.iter().all(|(watched, _)| watched != path) {
+            self.files.push((path.to_path_buf(), modified(path)));
+        }
+    }
+
+    // Files modified since the previous poll (none before "interval" passed).
+    // A file being removed isn't a change : editors often replace files by renaming,
+    // it changes when it's back
+    pub fn poll(&mut self) -> Vec<PathBuf> {
+        let now = Instant::now();
+        if self
+            .last_poll
+            .is_some_and(|last_poll| now - last_poll < self.interval)
+        {
+            return Vec::new();
+        }
+        self.last_poll = Some(now);
+
+        let mut changed = Vec::new();
+        for (path, seen) in &mut self.files {
+            let current = modified(path);
+            if current.is_some() && current != *seen {
+                changed.push(path.clone());
+            }
+            *seen = current;
+        }
+        changed
+    }
+
+    // The files as they are now aren't changes (to ignore what the app wrote itself)
+    pub fn mark_seen(&mut self) {
+        for (path, seen) in &mut self.files {
+            *seen = modified(path);
+        }
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
@@ -0,0 +1,69 @@
+use std::{
+    fs::{self, File},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use crate::app::temp_dir::TempDir;
+
+use super::FileWatcher;
+
+// A file of its own in a test's directory
+fn temp_file(directory: &TempDir) -> PathBuf {
+    directory.write("watched", "first")
+}
+
+// Modification times can be coarse : set them rather than waiting
+fn touch(path: &Path, seconds_later: u64) {
+    let modified = SystemTime::now() + Duration::from_secs(seconds_later);
+    File::options()
+        .write(true)
+        .open(path)
+        .unwrap()
+        .set_modified(modified)
+        .unwrap();
+}
+
+#[test]
+fn reports_each_change_once() {
+    let directory = TempDir::new("file_watcher_changes");
+    let path = temp_file(&directory);
+    let mut watcher = FileWatcher::new(Duration::ZERO);
+    watcher.watch(&path);
+    assert!(watcher.poll().is_empty());
+
+    touch(&path, 10);
+    assert_eq!(watcher.poll(), std::slice::from_ref(&path));
+    assert!(watcher.poll().is_empty());
+
+    // Written by the app itself
+    touch(&path, 20);
+    watcher.mark_seen();
+    assert!(watcher.poll().is_empty());
+}
+
+// Replaced by renaming : nothing while it's missing, a change once it's back
+#[test]
+fn removed_files_change_when_back() {
+    let directory = TempDir::new("file_watcher_removed");
+    let path = temp_file(&directory);
+    let mut watcher = FileWatcher::new(Duration::ZERO);
+    watcher.watch(&path);
+
+    fs::remove_file(&path).unwrap();
+    assert!(watcher.poll().is_empty());
+    fs::write(&path, "second").unwrap();
+    assert_eq!(watcher.poll(), std::slice::from_ref(&path));
+}
+
+#[test]
+fn polls_at_most_every_interval() {
+    let directory = TempDir::new("file_watcher_interval");
+    let path = temp_file(&directory);
+    let mut watcher = FileWatcher::new(Duration::from_secs(3600));
+    watcher.watch(&path);
+    assert!(watcher.poll().is_empty());
+
+    touch(&path, 10);
+    assert!(watcher.poll().is_empty());
+}
@@ -1,54 +1,125 @@
 mod device;
+mod headless;
 mod mesher;
 mod presenter;
 mod renderer;
+mod screenshot;
 
-use crate::app::{instance::Instance, model::Camera};
-use ash::vk::{Fence, Semaphore, SurfaceKHR};
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use crate::app::{
+    instance::Instance,
+    model::{Camera, Lighting},
+};
+use ash::vk::{Extent2D, Fence, ImageLayout, Semaphore, SurfaceKHR};
 pub use device::Device;
-pub use mesher::ToMesh;
+use glam::Mat4;
+pub use headless::HeadlessEngine;
+pub use mesher::{load_gltf, load_obj, Material, Mesh, MeshKey, TextureKey, ToMesh};
 pub use presenter::Presenter;
-pub use renderer::Renderer;
+pub use renderer::{Renderer, ShaderError};
+pub use screenshot::{save_png, ScreenshotError};
+
+pub const DEFAULT_FRAMES_IN_FLIGHT: usize = 2;
+
+// Meshes to upload and draws (model matrix, geometry, material) of a frame
+type Translated = (Vec<(MeshKey, Mesh)>, Vec<(Mat4, MeshKey, Material)>);
 
 // Given a surfaceKHR :
-// - Creates meshes from objects = mesher (hold no data)
+// - Creates meshes from objects = mesher (hold no data), once per geometry
 // - Renders imgs from meshes = renderer
 // - Presents imgs = presenter
+// Recreates the swapchain when the window changes, pauses while it is minimized
+// Up to "frames_in_flight" frames are prepared while the GPU renders the previous ones
+// Screenshots are taken from the next rendered image, before it's presented
 pub struct GraphicsEngine {
     // Essentials
     surface: SurfaceKHR,
     device: Device,
+    // Window
+    window_extent: Extent2D,
+    swapchain_out_of_date: bool,
     // Missions
     presenter: Presenter,
     renderer: Renderer,
-    // Syncs
+    // Syncs (one per frame in flight)
+    current_frame: usize,
+    frame_syncs: Vec<FrameSyncs>,
+    // Fence of the frame rendering to each swapchain image (null if none)
+    images_in_flight: Vec<Fence>,
+    // Signaled when each swapchain image is rendered, waited by its presentation
+    // (per image : a frame's semaphore could still be waited by a previous present)
+    rendering_done: Vec<Semaphore>,
+    // Where to save the next frame (if requested), and how the last save went
+    screenshot_path: Option<PathBuf>,
+    screenshot_outcome: Option<(PathBuf, Result<(), ScreenshotError>)>,
+}
+
+struct FrameSyncs {
     image_available: Semaphore,
-    rendering_done: Semaphore,
     fence_rendering_done: Fence,
 }
 
+impl FrameSyncs {
+    fn new(device: &Device) -> FrameSyncs {
+        FrameSyncs {
+            image_available: device.bp_new_semaphore(),
+            fence_rendering_done: device.bp_new_fence(true),
+        }
+    }
+
+    fn destroy(&mut self, device: &Device) {
+        unsafe {
+            device.destroy_semaphore(self.image_available, None);
+            device.destroy_fence(self.fence_rendering_done, None);
+        }
+    }
+}
+
 impl GraphicsEngine {
-    pub fn new(instance: &Instance, surface: SurfaceKHR) -> GraphicsEngine {
+    pub fn new(
+        instance: &Instance,
+        surface: SurfaceKHR,
+        window_extent: Extent2D,
+        frames_in_flight: usize,
+    ) -> GraphicsEngine {
         // Essentials
-        let device = Device::new(instance, &surface);
+        let device = Device::new(instance, Some(&surface));
 
         // Missions
-        let presenter = Presenter::new(&device, &surface);
-        let renderer = Renderer::new(&device, presenter.swapchain_images());
+        let presenter = Presenter::new(&device, &surface, window_extent);
+        let renderer = Renderer::new(
+            &device,
+            presenter.swapchain_images(),
+            device.infos.present().surface_format.format,
+            ImageLayout::PRESENT_SRC_KHR,
+            presenter.extent(),
+            frames_in_flight,
+        );
 
         // Syncs
-        let image_available = device.bp_new_semaphore();
-        let rendering_done = device.bp_new_semaphore();
-        let fence_rendering_done = device.bp_new_fence(true);
+        let frame_syncs = (0..frames_in_flight)
+            .map(|_| FrameSyncs::new(&device))
+            .collect();
+        let images_in_flight = vec![Fence::null(); presenter.swapchain_images().len()];
+        let rendering_done = new_semaphores(&device, presenter.swapchain_images().len());
 
         GraphicsEngine {
             surface,
             device,
+            window_extent,
+            swapchain_out_of_date: false,
             presenter,
             renderer,
-            image_available,
+            current_frame: 0,
+            frame_syncs,
+            images_in_flight,
             rendering_done,
-            fence_rendering_done,
+            screenshot_path: None,
+            screenshot_outcome: None,
         }
     }
 
@@ -58,9 +129,10 @@ impl GraphicsEngine {
             // wait unfinished work
             self.device.device_wait_idle().unwrap();
             // destroy syncs
-            self.device.destroy_semaphore(self.image_available, None);
-            self.device.destroy_semaphore(self.rendering_done, None);
-            self.device.destroy_fence(self.fence_rendering_done, None);
+            for syncs in &mut self.frame_syncs {
+                syncs.destroy(&self.device);
+            }
+            destroy_semaphores(&self.device, &self.rendering_done);
             // destroy missions
             self.presenter.destroy(&self.device);
             self.renderer.destroy(&self.device);
@@ -69,36 +141,165 @@ impl GraphicsEngine {
         }
     }
 
-    pub fn frame(&mut self, objects: Vec<&dyn ToMesh>, camera: &Camera) {
-        // Wait last rendering
-        self.device
-            .bp_wait_reset_fence(self.fence_rendering_done, None);
+    // Window's inner size changed (a null extent means minimized)
+    pub fn resize(&mut self, window_extent: Extent2D) {
+        self.window_extent = window_extent;
+        self.swapchain_out_of_date = true;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.window_extent.width == 0 || self.window_extent.height == 0
+    }
+
+    // The next frame will be saved as a PNG to "path" (see "take_screenshot_outcome")
+    pub fn request_screenshot(&mut self, path: PathBuf) {
+        self.screenshot_path = Some(path);
+    }
+
+    // Where the requested screenshot went and whether it was saved, once its frame is rendered
+    pub fn take_screenshot_outcome(&mut self) -> Option<(PathBuf, Result<(), ScreenshotError>)> {
+        self.screenshot_outcome.take()
+    }
+
+    // "key" won't be drawn again : its geometry's device memory can be reused
+    pub fn invalidate_mesh(&mut self, key: &MeshKey) {
+        self.renderer.invalidate_mesh(key);
+    }
+
+    // "key" won't be sampled again : its image can be destroyed
+    pub fn invalidate_texture(&mut self, key: &TextureKey) {
+        self.renderer.invalidate_texture(key);
+    }
+
+    // What the shaders are built from (to watch for changes)
+    pub fn shader_files(&self) -> Vec<PathBuf> {
+        self.renderer.shader_files()
+    }
+
+    // Shaders changed : rebuild the pipeline from them, kept as it was when they don't compile
+    pub fn reload_shaders(&mut self) -> Result<(), ShaderError> {
+        self.renderer.reload_shaders(&self.device)
+    }
+
+    pub fn frame(&mut self, objects: Vec<&dyn ToMesh>, camera: &Camera, lighting: &Lighting) {
+        // Nothing to present on a minimized window
+        if self.is_paused() {
+            return;
+        }
+        if self.swapchain_out_of_date {
+            self.recreate_swapchain();
+        }
 
-        // Acquire next image
-        let (image_idx, _) = self
+        let syncs = &self.frame_syncs[self.current_frame];
+
+        // Wait this frame's last rendering
+        self.device.bp_wait_fence(syncs.fence_rendering_done, None);
+
+        // Acquire next image (skip the frame if the swapchain can't present anymore)
+        let Some(image_idx) = self
             .presenter
-            .acquire_next_image(&self.device, self.image_available);
+            .acquire_next_image(&self.device, syncs.image_available)
+        else {
+            self.swapchain_out_of_date = true;
+            return;
+        };
 
-        // Translates objects into meshes
-        let meshes = objects
-            .into_iter()
-            .map(|object| (object.transform(), object.mesh()))
-            .collect();
+        // Wait the frame still rendering to this image (if any), then claim it
+        let image_in_flight = &mut self.images_in_flight[image_idx as usize];
+        if *image_in_flight != Fence::null() {
+            self.device.bp_wait_fence(*image_in_flight, None);
+        }
+        *image_in_flight = syncs.fence_rendering_done;
+        self.device.bp_reset_fence(syncs.fence_rendering_done);
+
+        let (uploads, draws) = translate(&self.renderer, objects);
 
         // Render to it
         self.renderer.submit_render(
             &self.device,
-            meshes,
+            self.current_frame,
+            uploads,
+            draws,
             camera,
+            lighting,
             image_idx,
-            self.device.infos.capabilities.current_extent,
-            self.image_available,
-            self.rendering_done,
-            self.fence_rendering_done,
+            Some(syncs.image_available),
+            Some(self.rendering_done[image_idx as usize]),
+            syncs.fence_rendering_done,
         );
 
+        // Capture it (if requested)
+        if let Some(path) = self.screenshot_path.take() {
+            let outcome = self.screenshot(image_idx, &path);
+            self.screenshot_outcome = Some((path, outcome));
+        }
+
         // Present it
+        self.swapchain_out_of_date = self.presenter.present(
+            &self.device,
+            image_idx,
+            self.rendering_done[image_idx as usize],
+        );
+
+        // Next frame
+        self.current_frame = (self.current_frame + 1) % self.frame_syncs.len();
+    }
+
+    // Copy the rendered (not yet presented) swapchain image and save it as a PNG
+    fn screenshot(&self, image_idx: u32, path: &Path) -> Result<(), ScreenshotError> {
+        if !self.presenter.is_capturable() {
+            return Err(ScreenshotError::NotCapturable);
+        }
+        let image = self.presenter.swapchain_images()[image_idx as usize];
+        let pixels = self
+            .renderer
+            .read_back(&self.device, image, ImageLayout::PRESENT_SRC_KHR)?;
+        let format = self.device.infos.present().surface_format.format;
+        let rgba = screenshot::to_rgba8(format, pixels)?;
+        save_png(path, self.presenter.extent(), &rgba)
+    }
+
+    fn recreate_swapchain(&mut self) {
+        unsafe { self.device.device_wait_idle() }.expect("Failed to wait device idle.");
         self.presenter
-            .present(&self.device, image_idx, self.rendering_done);
+            .recreate(&self.device, &self.surface, self.window_extent);
+        self.renderer.recreate(
+            &self.device,
+            self.presenter.swapchain_images(),
+            self.presenter.extent(),
+        );
+        let image_count = self.presenter.swapchain_images().len();
+        self.images_in_flight = vec![Fence::null(); image_count];
+        // The device is idle : no present waits on them anymore
+        destroy_semaphores(&self.device, &self.rendering_done);
+        self.rendering_done = new_semaphores(&self.device, image_count);
+        self.swapchain_out_of_date = false;
     }
 }
+
+fn new_semaphores(device: &Device, count: usize) -> Vec<Semaphore> {
+    (0..count).map(|_| device.bp_new_semaphore()).collect()
+}
+
+fn destroy_semaphores(device: &Device, semaphores: &[Semaphore]) {
+    for semaphore in semaphores {
+        unsafe { device.destroy_semaphore(*semaphore, None) };
+    }
+}
+
+// Translates objects into draws, and into meshes only for geometry the renderer doesn't have
+fn translate(renderer: &Renderer, objects: Vec<&dyn ToMesh>) -> Translated {
+    let mut uploads = Vec::new();
+    let mut uploading = HashSet::new();
+    let draws = objects
+        .into_iter()
+        .map(|object| {
+            let key = object.mesh_key();
+            if !renderer.has_mesh(&key) && uploading.insert(key) {
+                uploads.push((key, object.mesh()));
+            }
+            (object.transform(), key, object.material())
+        })
+        .collect();
+    (uploads, draws)
+}
@@ -1,12 +1,17 @@
 mod boilerplate;
 mod buffer;
+mod image;
 mod physical_device;
 
 use crate::app::instance::Instance;
 
 pub use buffer::{Buffer, MappedBuffer};
+pub use image::Image;
 
-use ash::vk::{self, PhysicalDevice, SurfaceKHR};
+use ash::{
+    prelude::VkResult,
+    vk::{self, PhysicalDevice, SurfaceCapabilitiesKHR, SurfaceKHR},
+};
 use physical_device::PhysicalDeviceInfos;
 use std::{ffi::c_char, ops::Deref};
 use vk_mem::{Allocator, AllocatorCreateInfo};
@@ -14,7 +19,7 @@ use vk_mem::{Allocator, AllocatorCreateInfo};
 const SWAPCHAIN_KHR_EXTENSION: *const c_char = c"VK_KHR_swapchain".as_ptr();
 
 // Custom device for rendering :
-// - swapchainKHR extension + support for presenting on "surface"
+// - swapchainKHR extension + support for presenting on "surface" (neither when headless)
 // - Hold infos about the physical device in use and the surface
 // - Hold a VMA instance
 pub struct Device {
@@ -22,8 +27,10 @@ pub struct Device {
     pub infos: PhysicalDeviceInfos,
     // Option bc allocator must drop before device destruction
     allocator: Option<vk_mem::Allocator>,
-    // swapchainKHR extension fns
-    swapchain_khr_device: ash::khr::swapchain::Device,
+    // swapchainKHR extension fns (None when headless)
+    swapchain_khr_device: Option<ash::khr::swapchain::Device>,
+    // surfaceKHR extension fns (surface properties change with the window, None when headless)
+    surface_khr_instance: Option<ash::khr::surface::Instance>,
 }
 
 // Deref : ash::Device
@@ -45,22 +52,49 @@ impl Drop for Device {
 }
 
 impl Device {
-    pub fn new(instance: &Instance, surface: &SurfaceKHR) -> Device {
-        let infos = physical_device::select_physical_device(instance, surface)
-            .expect("Failed to find a suitable physical device.");
-        let device = create_device(instance, &infos);
-        let allocator = Some(create_allocator(instance, &device, infos.physical_device));
-        let swapchain_khr_device = ash::khr::swapchain::Device::new(instance, &device);
-        Device {
+    // "surface" : None when headless (rendering offscreen only)
+    pub fn new(instance: &Instance, surface: Option<&SurfaceKHR>) -> Device {
+        Device::try_new(instance, surface).expect("Failed to create a device.")
+    }
+
+    // None when no physical device is suitable, or it fails to be set up
+    pub fn try_new(instance: &Instance, surface: Option<&SurfaceKHR>) -> Option<Device> {
+        let infos = physical_device::select_physical_device(instance, surface).ok()?;
+        let device = create_device(instance, &infos, surface.is_some()).ok()?;
+        let allocator = match create_allocator(instance, &device, infos.physical_device) {
+            Ok(allocator) => Some(allocator),
+            Err(_) => {
+                unsafe { device.destroy_device(None) };
+                return None;
+            }
+        };
+        let swapchain_khr_device =
+            surface.map(|_| ash::khr::swapchain::Device::new(instance, &device));
+        let surface_khr_instance = surface.map(|_| instance.surface_khr().clone());
+        Some(Device {
             device,
             infos,
             allocator,
             swapchain_khr_device,
-        }
+            surface_khr_instance,
+        })
     }
 
     pub fn swapchain_khr(&self) -> &ash::khr::swapchain::Device {
-        &self.swapchain_khr_device
+        self.swapchain_khr_device
+            .as_ref()
+            .expect("Headless device has no swapchain extension.")
+    }
+
+    // Current capabilities, "infos.capabilities" is only a snapshot from device selection
+    pub fn query_surface_capabilities(&self, surface: &SurfaceKHR) -> SurfaceCapabilitiesKHR {
+        unsafe {
+            self.surface_khr_instance
+                .as_ref()
+                .expect("Headless device has no surface.")
+                .get_physical_device_surface_capabilities(self.infos.physical_device, *surface)
+        }
+        .expect("Failed to query surface capabilities.")
     }
 
     pub fn allocator(&self) -> &vk_mem::Allocator {
@@ -69,43 +103,47 @@ impl Device {
     }
 }
 
-fn create_device(instance: &Instance, infos: &PhysicalDeviceInfos) -> ash::Device {
+fn create_device(
+    instance: &Instance,
+    infos: &PhysicalDeviceInfos,
+    presents: bool,
+) -> VkResult<ash::Device> {
     // SPECIFY : queues requested for each queue family
-    let graphics_queues_info = vk::DeviceQueueCreateInfo::default()
-        .queue_family_index(infos.graphics_idx)
-        .queue_priorities(&[0.5]);
-    let present_queues_info = vk::DeviceQueueCreateInfo::default()
-        .queue_family_index(infos.present_idx)
-        .queue_priorities(&[0.5]);
-    let transfer_queues_info = vk::DeviceQueueCreateInfo::default()
-        .queue_family_index(infos.transfer_idx)
-        .queue_priorities(&[0.5]);
-    let mut queue_create_infos = vec![
-        graphics_queues_info,
-        present_queues_info,
-        transfer_queues_info,
-    ];
+    let mut families = vec![infos.graphics_idx, infos.transfer_idx];
+    if presents {
+        families.push(infos.present().present_idx);
+    }
+    let mut queue_create_infos: Vec<vk::DeviceQueueCreateInfo> = families
+        .into_iter()
+        .map(|family| {
+            vk::DeviceQueueCreateInfo::default()
+                .queue_family_index(family)
+                .queue_priorities(&[0.5])
+        })
+        .collect();
     // removes duplicates
     queue_create_infos.sort_by_key(|info| info.queue_family_index);
     queue_create_infos.dedup_by_key(|info| info.queue_family_index);
 
     // SPECIFY : extensions
-    let swapchain_extension = vec![SWAPCHAIN_KHR_EXTENSION];
-    let extensions = [swapchain_extension].concat();
+    let extensions = if presents {
+        vec![SWAPCHAIN_KHR_EXTENSION]
+    } else {
+        Vec::new()
+    };
 
     // CREATE : device
     let create_info = vk::DeviceCreateInfo::default()
         .queue_create_infos(&queue_create_infos)
         .enabled_extension_names(&extensions);
     unsafe { instance.create_device(infos.physical_device, &create_info, None) }
-        .expect("Failed to create device.")
 }
 
 fn create_allocator(
     instance: &Instance,
     device: &ash::Device,
     physical_device: PhysicalDevice,
-) -> Allocator {
+) -> VkResult<Allocator> {
     let create_info = AllocatorCreateInfo::new(instance, device, physical_device);
-    unsafe { Allocator::new(create_info).expect("Failed to create allocator.") }
+    unsafe { Allocator::new(create_info) }
 }
@@ -1,11 +1,24 @@
 use ash::vk::{
     DescriptorPool, DescriptorSet, DescriptorSetAllocateInfo, DescriptorSetLayout, Fence,
-    FenceCreateFlags, FenceCreateInfo, Semaphore, SemaphoreCreateInfo,
+    FenceCreateFlags, FenceCreateInfo, Semaphore, SemaphoreCreateInfo, SharingMode,
 };
 
 use super::Device;
 
 impl Device {
+    // Sharing
+    // Mode and distinct families for a resource used by "families" queues (they may be the same)
+    pub fn bp_sharing(&self, families: &[u32]) -> (SharingMode, Vec<u32>) {
+        let mut families = families.to_vec();
+        families.sort();
+        families.dedup();
+        if families.len() > 1 {
+            (SharingMode::CONCURRENT, families)
+        } else {
+            (SharingMode::EXCLUSIVE, families)
+        }
+    }
+
     // Sync
     pub fn bp_new_semaphore(&self) -> Semaphore {
         let semaphore_create_info = SemaphoreCreateInfo::default();
@@ -20,18 +33,13 @@ impl Device {
         };
         unsafe { self.create_fence(&fence_create_info, None) }.expect("Failed to create fence.")
     }
-    pub fn bp_wait_reset_fence(&self, fence: Fence, timeout: Option<u64>) {
-        let fences = [fence];
-        self.bp_wait_reset_fences(&fences, true, timeout);
-    }
-    fn bp_wait_reset_fences(&self, fences: &[Fence], wait_all: bool, timeout: Option<u64>) {
+    pub fn bp_wait_fence(&self, fence: Fence, timeout: Option<u64>) {
         let timeout = timeout.unwrap_or(u64::MAX);
-        unsafe {
-            self.wait_for_fences(fences, wait_all, timeout)
-                .expect("Failed to wait for the fences.");
-            self.reset_fences(fences)
-                .expect("Failed to reset the fences.");
-        }
+        unsafe { self.wait_for_fences(&[fence], true, timeout) }
+            .expect("Failed to wait for the fence.");
+    }
+    pub fn bp_reset_fence(&self, fence: Fence) {
+        unsafe { self.reset_fences(&[fence]) }.expect("Failed to reset the fence.");
     }
 
     // Descriptors
@@ -42,7 +50,7 @@ impl Device {
     ) -> Vec<DescriptorSet> {
         let allocate_info = DescriptorSetAllocateInfo::default()
             .descriptor_pool(*descriptor_pool)
-            .set_layouts(&set_layouts);
+            .set_layouts(set_layouts);
         unsafe {
             self.allocate_descriptor_sets(&allocate_info)
                 .expect("Failed to allocate descriptor set")
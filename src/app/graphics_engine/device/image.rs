@@ -0,0 +1,40 @@
+use ash::vk::ImageCreateInfo;
+use std::ops::Deref;
+use vk_mem::{Alloc, Allocation, AllocationCreateInfo};
+
+use super::Device;
+
+pub struct Image {
+    pub image: ash::vk::Image,
+    pub allocation: Allocation,
+}
+
+// Deref : ash::vk::Image
+impl Deref for Image {
+    type Target = ash::vk::Image;
+    fn deref(&self) -> &Self::Target {
+        &self.image
+    }
+}
+
+impl Device {
+    pub fn ct_create_image(
+        &self,
+        image_info: &ImageCreateInfo,
+        create_info: &AllocationCreateInfo,
+    ) -> Image {
+        let (image, allocation) = unsafe {
+            self.allocator()
+                .create_image(image_info, create_info)
+                .expect("Failed to create image.")
+        };
+        Image { image, allocation }
+    }
+
+    pub fn ct_destroy_image(&self, image: &mut Image) {
+        unsafe {
+            self.allocator()
+                .destroy_image(image.image, &mut image.allocation)
+        };
+    }
+}
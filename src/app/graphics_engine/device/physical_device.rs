@@ -1,6 +1,6 @@
 use ash::vk::{
-    ColorSpaceKHR, ExtensionProperties, Format, PhysicalDevice, PhysicalDeviceType, PresentModeKHR,
-    QueueFlags, SurfaceCapabilitiesKHR, SurfaceFormatKHR, SurfaceKHR,
+    ColorSpaceKHR, ExtensionProperties, Format, FormatFeatureFlags, PhysicalDevice,
+    PhysicalDeviceType, PresentModeKHR, QueueFlags, SurfaceFormatKHR, SurfaceKHR,
 };
 use std::ffi::{c_char, CStr};
 
@@ -11,23 +11,40 @@ use crate::app::instance::Instance;
 pub struct PhysicalDeviceInfos {
     pub physical_device: PhysicalDevice,
     score: u32,
-    pub present_idx: u32,
     pub graphics_idx: u32,
+    // Dedicated transfer family if any, else the graphics one
     pub transfer_idx: u32,
-    pub capabilities: SurfaceCapabilitiesKHR,
+    pub depth_format: Format,
+    // None when headless
+    present: Option<PresentInfos>,
+}
+
+// How the device presents to the surface
+#[derive(Debug)]
+pub struct PresentInfos {
+    pub present_idx: u32,
     pub surface_format: SurfaceFormatKHR,
     pub present_mode: PresentModeKHR,
 }
 
+impl PhysicalDeviceInfos {
+    pub fn present(&self) -> &PresentInfos {
+        self.present
+            .as_ref()
+            .expect("Headless device can't present.")
+    }
+}
+
 // returns most appropriate physical device along with its infos
+// ("surface" : None when headless, swapchain support isn't required then)
 pub fn select_physical_device(
     instance: &Instance,
-    surface: &SurfaceKHR,
+    surface: Option<&SurfaceKHR>,
 ) -> Result<PhysicalDeviceInfos, ()> {
     // Query all physical devices
     let physical_devices: Vec<PhysicalDeviceInfos> =
         unsafe { instance.enumerate_physical_devices() }
-            .expect("Failed to query physical devices.")
+            .map_err(|_| ())?
             .into_iter()
             .filter_map(|physical_device| {
                 query_physical_device_infos(instance, surface, physical_device).ok()
@@ -44,7 +61,7 @@ pub fn select_physical_device(
 // Query infos for a physical device (fails when the device is unsuitable)
 fn query_physical_device_infos(
     instance: &Instance,
-    surface: &SurfaceKHR,
+    surface: Option<&SurfaceKHR>,
     physical_device: PhysicalDevice,
 ) -> Result<PhysicalDeviceInfos, ()> {
     // fetching general device data
@@ -52,7 +69,7 @@ fn query_physical_device_infos(
         unsafe { instance.get_physical_device_queue_family_properties(physical_device) };
     let available_extensions =
         unsafe { instance.enumerate_device_extension_properties(physical_device) }
-            .expect("Failed to get device extensions.");
+            .map_err(|_| ())?;
     let properties = unsafe { instance.get_physical_device_properties(physical_device) };
 
     // REQUIRED
@@ -63,39 +80,87 @@ fn query_physical_device_infos(
         .position(|queue_family| queue_family.queue_flags.contains(QueueFlags::GRAPHICS))
         .ok_or(())? as u32; // Convert Option to Result : Ok for Some and Err for None
 
-    // transfer queue (!= graphics queue)
+    // transfer queue (a family without graphics is prefered, some drivers have a single family)
     let transfer_idx = queue_families
         .iter()
         .position(|queue_family| {
             queue_family.queue_flags.contains(QueueFlags::TRANSFER)
                 && !queue_family.queue_flags.contains(QueueFlags::GRAPHICS)
         })
-        .ok_or(())? as u32;
+        .map_or(graphics_idx, |idx| idx as u32);
+
+    // depth attachment support
+    let depth_format = choose_depth_format(instance, physical_device).ok_or(())?;
+
+    // presenting (when rendering to a surface), scored on its format and mode
+    let (present, present_score) = match surface {
+        Some(surface) => {
+            let (present, score) = query_present_infos(
+                instance,
+                surface,
+                physical_device,
+                queue_families.len(),
+                &available_extensions,
+            )?;
+            (Some(present), score)
+        }
+        None => (None, 0),
+    };
 
+    // SCORING
+    let mut score = 0;
+
+    // dedicated gpu are prefered
+    match properties.device_type {
+        PhysicalDeviceType::DISCRETE_GPU | PhysicalDeviceType::VIRTUAL_GPU => {
+            score += 10;
+        }
+        PhysicalDeviceType::INTEGRATED_GPU => {
+            score += 5;
+        }
+        _ => {
+            score += 1;
+        }
+    }
+
+    // SRGB_8 format and FIFO present mode are prefered
+    score += present_score;
+
+    Ok(PhysicalDeviceInfos {
+        physical_device,
+        score,
+        depth_format,
+        graphics_idx,
+        transfer_idx,
+        present,
+    })
+}
+
+// Query how the device would present on "surface" and score it (fails when it can't)
+fn query_present_infos(
+    instance: &Instance,
+    surface: &SurfaceKHR,
+    physical_device: PhysicalDevice,
+    queue_family_count: usize,
+    available_extensions: &[ExtensionProperties],
+) -> Result<(PresentInfos, u32), ()> {
     // surface support
-    let present_idx = queue_families
-        .iter()
-        .enumerate()
-        .position(|(idx, _)| unsafe {
+    let present_idx = (0..queue_family_count as u32)
+        .find(|idx| unsafe {
             instance
                 .surface_khr()
-                .get_physical_device_surface_support(physical_device, idx as u32, *surface)
+                .get_physical_device_surface_support(physical_device, *idx, *surface)
                 .unwrap()
         })
-        .ok_or(())? as u32;
+        .ok_or(())?;
 
     // swapchain extension support
-    if !is_extension_available(SWAPCHAIN_KHR_EXTENSION, &available_extensions) {
+    if !is_extension_available(SWAPCHAIN_KHR_EXTENSION, available_extensions) {
         return Err(());
     }
 
-    // fetching surface capabilities, format, present_mode for this device
-    let capabilities = unsafe {
-        instance
-            .surface_khr()
-            .get_physical_device_surface_capabilities(physical_device, *surface)
-    }
-    .unwrap();
+    // fetching surface format, present_mode for this device
+    // (capabilities change with the window : they're queried when creating swapchains)
     let available_surface_formats = unsafe {
         instance
             .surface_khr()
@@ -108,46 +173,20 @@ fn query_physical_device_infos(
             .get_physical_device_surface_present_modes(physical_device, *surface)
     }
     .unwrap();
-
-    // SCORING
-    let mut score = 0;
-
-    // dedicated gpu are prefered
-    match properties.device_type {
-        PhysicalDeviceType::DISCRETE_GPU | PhysicalDeviceType::VIRTUAL_GPU => {
-            score += 10;
-        }
-        PhysicalDeviceType::INTEGRATED_GPU => {
-            score += 5;
-        }
-        _ => {
-            score += 1;
-        }
-    }
-
-    // SRGB_8 format is prefered
     let (surface_format, surface_format_score) = choose_best_format(&available_surface_formats);
-    score += surface_format_score;
-
-    // FIFO present mode is prefered
     let (present_mode, present_mode_score) = choose_best_present_mode(&available_present_modes);
-    score += present_mode_score;
 
-    Ok(PhysicalDeviceInfos {
-        physical_device,
-        score,
-        capabilities,
+    let present = PresentInfos {
+        present_idx,
         surface_format,
         present_mode,
-        graphics_idx,
-        present_idx,
-        transfer_idx,
-    })
+    };
+    Ok((present, surface_format_score + present_mode_score))
 }
 
 fn is_extension_available(
     extension: *const c_char,
-    available_extensions: &Vec<ExtensionProperties>,
+    available_extensions: &[ExtensionProperties],
 ) -> bool {
     let extension = unsafe { CStr::from_ptr(extension) };
     available_extensions.iter().any(|available_extension| {
@@ -155,7 +194,7 @@ fn is_extension_available(
     })
 }
 
-fn choose_best_format(available_formats: &Vec<SurfaceFormatKHR>) -> (SurfaceFormatKHR, u32) {
+fn choose_best_format(available_formats: &[SurfaceFormatKHR]) -> (SurfaceFormatKHR, u32) {
     available_formats
         .iter()
         .enumerate()
@@ -173,9 +212,7 @@ fn choose_best_format(available_formats: &Vec<SurfaceFormatKHR>) -> (SurfaceForm
         .unwrap()
 }
 
-fn choose_best_present_mode(
-    available_present_modes: &Vec<PresentModeKHR>,
-) -> (PresentModeKHR, u32) {
+fn choose_best_present_mode(available_present_modes: &[PresentModeKHR]) -> (PresentModeKHR, u32) {
     available_present_modes
         .iter()
         .enumerate()
@@ -192,3 +229,19 @@ fn choose_best_present_mode(
         .max_by_key(|(_, score)| *score)
         .unwrap()
 }
+
+// First format (by preference) usable as an optimal-tiling depth attachment
+fn choose_depth_format(instance: &Instance, physical_device: PhysicalDevice) -> Option<Format> {
+    let candidates = [
+        Format::D32_SFLOAT,
+        Format::D32_SFLOAT_S8_UINT,
+        Format::D24_UNORM_S8_UINT,
+    ];
+    candidates.into_iter().find(|format| {
+        let properties =
+            unsafe { instance.get_physical_device_format_properties(physical_device, *format) };
+        properties
+            .optimal_tiling_features
+            .contains(FormatFeatureFlags::DEPTH_STENCIL_ATTACHMENT)
+    })
+}
@@ -0,0 +1,139 @@
+#[cfg(test)]
+mod tests;
+
+use ash::vk::{
+    Extent2D, Extent3D, Fence, Format, ImageCreateInfo, ImageLayout, ImageTiling, ImageType,
+    ImageUsageFlags, MemoryPropertyFlags, SampleCountFlags, SharingMode,
+};
+use vk_mem::AllocationCreateInfo;
+
+use crate::app::{
+    instance::Instance,
+    model::{Camera, Lighting},
+};
+
+use super::{device::Image, translate, Device, Renderer, ToMesh};
+
+// Format of the offscreen image, thus of the pixels read back
+pub const HEADLESS_FORMAT: Format = Format::R8G8B8A8_SRGB;
+
+// Renders like the GraphicsEngine, without surface nor swapchain (nor their extensions) :
+// each frame goes to a single offscreen color image, then is read back to the CPU.
+// Works with software drivers (lavapipe) on machines without display
+pub struct HeadlessEngine {
+    // Essentials
+    device: Device,
+    // Target
+    image: Image,
+    // Missions
+    renderer: Renderer,
+    // Syncs
+    fence_rendering_done: Fence,
+}
+
+impl HeadlessEngine {
+    pub fn new(instance: &Instance, extent: Extent2D) -> HeadlessEngine {
+        HeadlessEngine::try_new(instance, extent).expect("Failed to create a device.")
+    }
+
+    // None when no physical device can render (or be set up)
+    pub fn try_new(instance: &Instance, extent: Extent2D) -> Option<HeadlessEngine> {
+        // Essentials
+        let device = Device::try_new(instance, None)?;
+
+        // Target
+        let image = allocate_target(&device, extent);
+
+        // Missions (one frame at a time : each is waited for its pixels)
+        let renderer = Renderer::new(
+            &device,
+            &[*image],
+            HEADLESS_FORMAT,
+            ImageLayout::TRANSFER_SRC_OPTIMAL,
+            extent,
+            1,
+        );
+
+        // Syncs
+        let fence_rendering_done = device.bp_new_fence(false);
+
+        Some(HeadlessEngine {
+            device,
+            image,
+            renderer,
+            fence_rendering_done,
+        })
+    }
+
+    // Destroy vulkan objects (order matters)
+    pub fn destroy(&mut self) {
+        unsafe {
+            // wait unfinished work
+            self.device.device_wait_idle().unwrap();
+            // destroy syncs
+            self.device.destroy_fence(self.fence_rendering_done, None);
+        }
+        // destroy missions
+        self.renderer.destroy(&self.device);
+        // destroy target
+        self.device.ct_destroy_image(&mut self.image);
+    }
+
+    pub fn extent(&self) -> Extent2D {
+        self.renderer.extent()
+    }
+
+    // Render "objects" seen by "camera" and lit by "lighting", and wait for the pixels :
+    // RGBA rows from top to bottom, sRGB encoded, 4 bytes per pixel
+    pub fn render(
+        &mut self,
+        objects: Vec<&dyn ToMesh>,
+        camera: &Camera,
+        lighting: &Lighting,
+    ) -> Vec<u8> {
+        let (uploads, draws) = translate(&self.renderer, objects);
+        self.renderer.submit_render(
+            &self.device,
+            0,
+            uploads,
+            draws,
+            camera,
+            lighting,
+            0,
+            None,
+            None,
+            self.fence_rendering_done,
+        );
+        // The next frame reuses the frame's resources
+        self.device.bp_wait_fence(self.fence_rendering_done, None);
+        self.device.bp_reset_fence(self.fence_rendering_done);
+
+        self.renderer
+            .read_back(&self.device, *self.image, ImageLayout::TRANSFER_SRC_OPTIMAL)
+            .expect("Failed to read back the rendering.")
+    }
+}
+
+// Color image standing for a swapchain image, also copied from when read back
+fn allocate_target(device: &Device, extent: Extent2D) -> Image {
+    let queue_family_indices = [device.infos.graphics_idx];
+    let image_info = ImageCreateInfo::default()
+        .image_type(ImageType::TYPE_2D)
+        .format(HEADLESS_FORMAT)
+        .extent(Extent3D::from(extent).depth(1))
+        .mip_levels(1)
+        .array_layers(1)
+        .samples(SampleCountFlags::TYPE_1)
+        .tiling(ImageTiling::OPTIMAL)
+        .usage(ImageUsageFlags::COLOR_ATTACHMENT | ImageUsageFlags::TRANSFER_SRC)
+        .queue_family_indices(&queue_family_indices)
+        .sharing_mode(SharingMode::EXCLUSIVE)
+        .initial_layout(ImageLayout::UNDEFINED);
+
+    let create_info = AllocationCreateInfo {
+        required_flags: MemoryPropertyFlags::DEVICE_LOCAL,
+        ..Default::default()
+    };
+
+    device.ct_create_image(&image_info, &create_info)
+}
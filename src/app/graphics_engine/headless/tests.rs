@@ -0,0 +1,196 @@
+// Golden images : canonical scenes rendered offscreen, compared to the references checked in
+// tests/golden. They need a vulkan driver (lavapipe is enough), so they are ignored by default :
+// run them with "cargo test -- --ignored", where a missing driver fails them.
+//
+// A missing reference fails the test. Set GOLDEN_UPDATE=1 to record the references from the
+// renders (check them after review), after an intended rendering change.
+// On mismatch, the render and a diff image (differing pixels in red) are written to
+// target/golden for inspection.
+
+use std::{
+    fs::File,
+    io::BufReader,
+    path::{Path, PathBuf},
+};
+
+use ash::vk::Extent2D;
+use glam::{Quat, Vec3};
+
+use crate::app::{
+    instance::Instance,
+    model::{
+        object::{Cube, Octahedron},
+        space::{Coord, Orientation},
+        Camera, Lighting,
+    },
+};
+
+use super::{super::save_png, HeadlessEngine, ToMesh};
+
+const EXTENT: Extent2D = Extent2D {
+    width: 128,
+    height: 128,
+};
+
+// A pixel differs when a channel is off by more than this (rasterization and
+// precision vary a bit between drivers), the render differs beyond MAX_DIFFERING_RATIO of them
+const CHANNEL_TOLERANCE: u8 = 8;
+const MAX_DIFFERING_RATIO: f32 = 0.005;
+
+fn golden_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/golden")
+}
+
+fn output_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("target/golden")
+}
+
+// RGBA8 pixels of "objects" seen by "camera"
+fn render(name: &str, objects: Vec<&dyn ToMesh>, camera: &Camera) -> Vec<u8> {
+    let instance = Instance::try_new_headless()
+        .unwrap_or_else(|| panic!("{name} : no vulkan driver to create an instance."));
+    let mut engine = HeadlessEngine::try_new(&instance, EXTENT)
+        .unwrap_or_else(|| panic!("{name} : no vulkan device to render with."));
+    let rgba = engine.render(objects, camera, &Lighting::default_lighting());
+    engine.destroy();
+    rgba
+}
+
+fn load_png(path: &Path) -> Option<(Extent2D, Vec<u8>)> {
+    let decoder = png::Decoder::new(BufReader::new(File::open(path).ok()?));
+    let mut reader = decoder
+        .read_info()
+        .expect("Failed to read reference header.");
+    let mut rgba = vec![0; reader.output_buffer_size().expect("Reference too big.")];
+    let info = reader
+        .next_frame(&mut rgba)
+        .expect("Failed to decode reference.");
+    assert_eq!(
+        (info.color_type, info.bit_depth),
+        (png::ColorType::Rgba, png::BitDepth::Eight),
+        "References must be RGBA8."
+    );
+    rgba.truncate(info.buffer_size());
+    let extent = Extent2D {
+        width: info.width,
+        height: info.height,
+    };
+    Some((extent, rgba))
+}
+
+// Differing pixels in red, the others as a faded reference
+fn diff_image(expected: &[u8], actual: &[u8]) -> (usize, Vec<u8>) {
+    let mut differing = 0;
+    let mut diff = Vec::with_capacity(expected.len());
+    for (expected, actual) in expected.chunks_exact(4).zip(actual.chunks_exact(4)) {
+        let differs = expected
+            .iter()
+            .zip(actual)
+            .any(|(e, a)| e.abs_diff(*a) > CHANNEL_TOLERANCE);
+        if differs {
+            differing += 1;
+            diff.extend([u8::MAX, 0, 0, u8::MAX]);
+        } else {
+            diff.extend(expected[..3].iter().map(|channel| channel / 4));
+            diff.push(u8::MAX);
+        }
+    }
+    (differing, diff)
+}
+
+fn check_golden(name: &str, objects: Vec<&dyn ToMesh>, camera: &Camera) {
+    let actual = render(name, objects, camera);
+
+    let reference = golden_dir().join(format!("{name}.png"));
+    if std::env::var_os("GOLDEN_UPDATE").is_some() {
+        std::fs::create_dir_all(golden_dir()).expect("Failed to create golden directory.");
+        save_png(&reference, EXTENT, &actual)
+            .unwrap_or_else(|err| panic!("Failed to record {name} : {err}"));
+        eprintln!("Recorded golden image {}", reference.display());
+        return;
+    }
+    let Some(expected) = load_png(&reference) else {
+        panic!(
+            "{name} : no reference at {}, record it with GOLDEN_UPDATE=1",
+            reference.display()
+        );
+    };
+    let (extent, expected) = expected;
+    assert_eq!(
+        (extent.width, extent.height),
+        (EXTENT.width, EXTENT.height),
+        "{name} : reference has another size."
+    );
+
+    let (differing, diff) = diff_image(&expected, &actual);
+    let pixel_count = (EXTENT.width * EXTENT.height) as f32;
+    if differing as f32 / pixel_count > MAX_DIFFERING_RATIO {
+        std::fs::create_dir_all(output_dir()).expect("Failed to create output directory.");
+        let actual_path = output_dir().join(format!("{name}.actual.png"));
+        let diff_path = output_dir().join(format!("{name}.diff.png"));
+        save_png(&actual_path, EXTENT, &actual).unwrap_or_else(|err| panic!("{err}"));
+        save_png(&diff_path, EXTENT, &diff).unwrap_or_else(|err| panic!("{err}"));
+        panic!(
+            "{name} : {differing} pixels differ from the reference, see {} and {}",
+            actual_path.display(),
+            diff_path.display()
+        );
+    }
+}
+
+#[test]
+#[ignore = "needs a vulkan driver"]
+fn golden_octahedron() {
+    let octahedron = Octahedron::new_unoriented(Coord::new(0., 0., 0.), 0.5);
+    let camera = Camera::new(Vec3::new(0., 1., 2.), Vec3::ZERO);
+    check_golden("octahedron", vec![&octahedron], &camera);
+}
+
+// The octahedron goes through the cube : depth testing decides which faces show
+#[test]
+#[ignore = "needs a vulkan driver"]
+fn golden_cube_octahedron_depth() {
+    let cube = Cube::new(
+        Coord::new(0., 0., 0.),
+        Orientation::from_quat(Quat::from_rotation_y(0.5)),
+        0.4,
+    );
+    let octahedron = Octahedron::new_unoriented(Coord::new(0.3, 0.2, 0.3), 0.5);
+    let camera = Camera::new(2. * Vec3::ONE, Vec3::ZERO);
+    check_golden("cube_octahedron_depth", vec![&cube, &octahedron], &camera);
+}
+
+// Same objects seen from fixed positions around them (front, side, above)
+#[test]
+#[ignore = "needs a vulkan driver"]
+fn golden_camera_positions() {
+    let cube = Cube::new_unoriented(Coord::new(-0.4, 0., 0.), 0.3);
+    let octahedron = Octahedron::new_unoriented(Coord::new(0.4, 0., 0.), 0.4);
+    let eyes = [
+        ("front", Vec3::new(0., 0., 3.)),
+        ("side", Vec3::new(3., 0.5, 0.)),
+        ("above", Vec3::new(0.5, 3., 0.5)),
+    ];
+    for (view, eye) in eyes {
+        let camera = Camera::new(eye, Vec3::ZERO);
+        check_golden(&format!("camera_{view}"), vec![&cube, &octahedron], &camera);
+    }
+}
+
+#[test]
+fn diff_tolerance() {
+    let expected = [100, 100, 100, 255, 0, 0, 0, 255];
+    let actual = [
+        100 + CHANNEL_TOLERANCE,
+        100,
+        100,
+        255,
+        0,
+        CHANNEL_TOLERANCE + 1,
+        0,
+        255,
+    ];
+    let (differing, diff) = diff_image(&expected, &actual);
+    assert_eq!(differing, 1);
+    assert_eq!(diff, [25, 25, 25, 255, 255, 0, 0, 255]);
+}
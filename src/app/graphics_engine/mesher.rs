@@ -1,9 +1,15 @@
+mod gltf;
+mod material;
 mod mesh;
+mod obj;
+mod primitives;
+#[cfg(test)]
+mod tests;
 mod translate;
 mod vertex;
 
-pub const MAX_VERTICES: u64 = 12;
-pub const MAX_INDICES: u64 = 32;
-
-pub use mesh::{Mesh, ToMesh};
+pub use self::gltf::load_gltf;
+pub use material::{Material, Texture, TextureKey};
+pub use mesh::{Mesh, MeshKey, ToMesh};
+pub use obj::load_obj;
 pub use vertex::Vertex;
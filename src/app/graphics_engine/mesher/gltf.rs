@@ -0,0 +1,178 @@
+use std::path::Path;
+
+use glam::{Mat3, Mat4, Vec2, Vec3, Vec4};
+use gltf::{image, mesh::Mode, Document, Node, Primitive};
+
+use crate::app::model::{
+    object::MeshObject,
+    space::{Coord, Orientation},
+};
+
+use super::{mesh::smooth_normals, Material, Mesh, MeshKey, Texture, Vertex};
+
+// Scales closer than this on every axis are uniform, matrices closer than this are equal
+const UNIFORM_SCALE_EPSILON: f32 = 1e-5;
+
+// One object per primitive of each node holding a mesh in the default scene (or the first one),
+// from a .gltf (with its buffers and images next to it or embedded) or a .glb file.
+// Node transforms are composed down the tree, then split onto the object's position,
+// orientation and size. What doesn't fit in a size (non-uniform scales, shears) is baked in the
+// vertices. Primitives keep their material's base color and base color texture.
+pub fn load_gltf(path: &Path) -> Result<Vec<MeshObject>, gltf::Error> {
+    let (document, buffers, images) = gltf::import(path)?;
+    // Images as textures, once for all the materials using them
+    let textures: Vec<Option<Texture>> = images.iter().map(to_texture).collect();
+    let meshes: Vec<Vec<MeshObject>> = document
+        .meshes()
+        .map(|mesh| {
+            mesh.primitives()
+                .filter_map(|primitive| read_primitive(&primitive, &buffers, &textures))
+                .collect()
+        })
+        .collect();
+
+    let mut objects = Vec::new();
+    if let Some(scene) = default_scene(&document) {
+        for node in scene.nodes() {
+            add_node_objects(&mut objects, &node, Mat4::IDENTITY, &meshes);
+        }
+    }
+    Ok(objects)
+}
+
+fn default_scene(document: &Document) -> Option<gltf::Scene<'_>> {
+    document
+        .default_scene()
+        .or_else(|| document.scenes().next())
+}
+
+// "meshes" : primitives of each mesh, at the origin
+fn add_node_objects(
+    objects: &mut Vec<MeshObject>,
+    node: &Node,
+    parent_transform: Mat4,
+    meshes: &[Vec<MeshObject>],
+) {
+    let transform = parent_transform * Mat4::from_cols_array_2d(&node.transform().matrix());
+    if let Some(mesh) = node.mesh() {
+        for primitive in &meshes[mesh.index()] {
+            objects.push(placed(transform, primitive.clone()));
+        }
+    }
+    for child in node.children() {
+        add_node_objects(objects, &child, transform, meshes);
+    }
+}
+
+// Nodes sharing a mesh share its key, unless the mesh gets part of the transform baked in
+fn placed(transform: Mat4, mut object: MeshObject) -> MeshObject {
+    let (scale, rotation, translation) = transform.to_scale_rotation_translation();
+    object.position = Coord::new(translation.x, translation.y, translation.z);
+    let uniform = (scale - Vec3::splat(scale.x)).abs().max_element() < UNIFORM_SCALE_EPSILON;
+    // Decomposing assumes there is no shear : check the parts give the transform back
+    let recomposed = Mat4::from_scale_rotation_translation(scale, rotation, translation);
+    let sheared = !recomposed.abs_diff_eq(transform, UNIFORM_SCALE_EPSILON * scale.max_element());
+    if sheared {
+        // All of it in the vertices
+        object.orientation = Orientation::initial();
+        object.size = 1.;
+        return baked(object, Mat3::from_mat4(transform));
+    }
+    object.orientation = Orientation::from_quat(rotation);
+    if uniform && scale.x > 0. {
+        object.size = scale.x;
+        return object;
+    }
+    object.size = 1.;
+    baked(object, Mat3::from_diagonal(scale))
+}
+
+// "object" with "linear" applied to its vertices, under a key of its own
+fn baked(object: MeshObject, linear: Mat3) -> MeshObject {
+    let mut mesh = object.mesh.clone();
+    let normal_matrix = linear.inverse().transpose();
+    for vertex in &mut mesh.vertices {
+        vertex.pos = linear * vertex.pos;
+        vertex.normal = (normal_matrix * vertex.normal).normalize_or_zero();
+    }
+    // Mirroring turns front faces into back faces
+    if linear.determinant() < 0. {
+        for triangle in mesh.indices.chunks_exact_mut(3) {
+            triangle.swap(1, 2);
+        }
+    }
+    let mut baked = MeshObject::new(object.position, object.orientation, object.size, mesh);
+    baked.material = object.material;
+    baked
+}
+
+// Triangle lists only (points, lines and strips are skipped), at the origin with its material
+fn read_primitive(
+    primitive: &Primitive,
+    buffers: &[gltf::buffer::Data],
+    textures: &[Option<Texture>],
+) -> Option<MeshObject> {
+    if primitive.mode() != Mode::Triangles {
+        return None;
+    }
+    let reader = primitive.reader(|buffer| Some(&buffers[buffer.index()]));
+    let positions: Vec<Vec3> = reader.read_positions()?.map(Vec3::from).collect();
+    let indices: Vec<u32> = match reader.read_indices() {
+        Some(indices) => indices.into_u32().collect(),
+        None => (0..positions.len() as u32).collect(),
+    };
+    let normals: Vec<Vec3> = match reader.read_normals() {
+        Some(normals) => normals.map(Vec3::from).collect(),
+        None => smooth_normals(&positions, &indices),
+    };
+    let pbr = primitive.material().pbr_metallic_roughness();
+    let (texture, tex_coord) = match pbr.base_color_texture() {
+        Some(info) => (
+            textures
+                .get(info.texture().source().index())
+                .cloned()
+                .flatten(),
+            info.tex_coord(),
+        ),
+        None => (None, 0),
+    };
+    let uvs: Vec<Vec2> = match reader.read_tex_coords(tex_coord) {
+        Some(uvs) => uvs.into_f32().map(Vec2::from).collect(),
+        None => vec![Vec2::ZERO; positions.len()],
+    };
+    let colors: Vec<Vec3> = match reader.read_colors(0) {
+        Some(colors) => colors.into_rgb_f32().map(Vec3::from).collect(),
+        None => vec![Vec3::ONE; positions.len()],
+    };
+
+    let vertices = (0..positions.len())
+        .map(|idx| Vertex::new(positions[idx], colors[idx], normals[idx], uvs[idx]))
+        .collect();
+    let mesh = Mesh { vertices, indices };
+    let mut object = MeshObject::with_key(
+        Coord::new(0., 0., 0.),
+        Orientation::initial(),
+        1.,
+        mesh,
+        MeshKey::unique(),
+    );
+    object.material = Material {
+        base_color: Vec4::from(pbr.base_color_factor()).truncate(),
+        texture,
+    };
+    Some(object)
+}
+
+// 8 bits RGB and RGBA images only (others leave materials with their base color)
+fn to_texture(image: &image::Data) -> Option<Texture> {
+    let pixels = match image.format {
+        image::Format::R8G8B8 => image
+            .pixels
+            .chunks_exact(3)
+            .flat_map(|texel| [texel[0], texel[1], texel[2], u8::MAX])
+            .collect(),
+        image::Format::R8G8B8A8 => image.pixels.clone(),
+        _ => return None,
+    };
+    Some(Texture::new(image.width, image.height, pixels))
+}
@@ -0,0 +1,58 @@
+use std::sync::{
+    atomic::{AtomicU32, Ordering},
+    Arc,
+};
+
+use glam::Vec3;
+
+// How a surface is colored : its vertex colors times "base_color" (linear),
+// times "texture" sampled at its uvs
+#[derive(Clone, Debug)]
+pub struct Material {
+    pub base_color: Vec3,
+    pub texture: Option<Texture>,
+}
+
+impl Material {
+    // Vertex colors only
+    pub fn plain() -> Material {
+        Material {
+            base_color: Vec3::ONE,
+            texture: None,
+        }
+    }
+}
+
+// RGBA pixels, 8 bits per channel sRGB encoded, rows from the top.
+// Uploaded once : the pixels of a key must never change
+#[derive(Clone, Debug)]
+pub struct Texture {
+    pub key: TextureKey,
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Arc<[u8]>,
+}
+
+// Identifies a texture : materials with equal keys share one uploaded image
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct TextureKey(u32);
+
+impl TextureKey {
+    pub fn unique() -> TextureKey {
+        static NEXT: AtomicU32 = AtomicU32::new(0);
+        TextureKey(NEXT.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+impl Texture {
+    // "pixels" : width * height RGBA texels
+    pub fn new(width: u32, height: u32, pixels: Vec<u8>) -> Texture {
+        assert_eq!(pixels.len(), 4 * width as usize * height as usize);
+        Texture {
+            key: TextureKey::unique(),
+            width,
+            height,
+            pixels: pixels.into(),
+        }
+    }
+}
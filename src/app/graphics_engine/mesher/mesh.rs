@@ -1,13 +1,62 @@
-use glam::Mat4;
+use std::sync::atomic::{AtomicU32, Ordering};
 
-use super::Vertex;
+use glam::{Mat4, Vec3};
 
+use super::{Material, Vertex};
+
+// "mesh" is only called when the renderer doesn't hold the geometry of "mesh_key" yet :
+// an object whose geometry changes must return a new key.
+// The material isn't part of the key : objects sharing a geometry can look different
 pub trait ToMesh {
     fn transform(&self) -> Mat4;
+    fn mesh_key(&self) -> MeshKey;
     fn mesh(&self) -> Mesh;
+    fn material(&self) -> Material {
+        Material::plain()
+    }
+}
+
+// Identifies a geometry : objects with equal keys share one uploaded mesh
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct MeshKey {
+    shape: &'static str,
+    params: [u32; 3],
+}
+
+impl MeshKey {
+    // Generated geometry, the same for the same shape and parameters (floats by their bits)
+    pub fn new(shape: &'static str, params: [u32; 3]) -> MeshKey {
+        MeshKey { shape, params }
+    }
+
+    // Geometry given as data (loaded files) : a key no other mesh has
+    pub fn unique() -> MeshKey {
+        static NEXT: AtomicU32 = AtomicU32::new(0);
+        MeshKey {
+            shape: "unique",
+            params: [NEXT.fetch_add(1, Ordering::Relaxed), 0, 0],
+        }
+    }
 }
 
+#[derive(Clone)]
 pub struct Mesh {
     pub vertices: Vec<Vertex>,
     pub indices: Vec<u32>,
 }
+
+// Area-weighted average of the normals of the faces around each vertex (for files without normals)
+pub fn smooth_normals(positions: &[Vec3], indices: &[u32]) -> Vec<Vec3> {
+    let mut normals = vec![Vec3::ZERO; positions.len()];
+    for triangle in indices.chunks_exact(3) {
+        let [a, b, c] = [triangle[0], triangle[1], triangle[2]].map(|idx| idx as usize);
+        let normal = (positions[b] - positions[a]).cross(positions[c] - positions[a]);
+        for idx in [a, b, c] {
+            normals[idx] += normal;
+        }
+    }
+    normals
+        .into_iter()
+        .map(|normal| normal.try_normalize().unwrap_or(Vec3::Y))
+        .collect()
+}
@@ -0,0 +1,67 @@
+use std::path::Path;
+
+use glam::{Vec2, Vec3};
+use tobj::{LoadError, LoadOptions};
+
+use super::{mesh::smooth_normals, Mesh, Vertex};
+
+// Color of faces without a material (or without a readable MTL file)
+const DEFAULT_COLOR: Vec3 = Vec3::ONE;
+
+// Every model of the file merged in a single mesh. Polygons are triangulated (fan, winding kept)
+// and each distinct position/normal/uv tuple becomes one indexed vertex.
+// Colors come from the MTL diffuse colors, missing normals are smoothed from the faces.
+pub fn load_obj(path: &Path) -> Result<Mesh, LoadError> {
+    let options = LoadOptions {
+        triangulate: true,
+        single_index: true,
+        ignore_points: true,
+        ignore_lines: true,
+    };
+    let (models, materials) = tobj::load_obj(path, &options)?;
+    // A missing or broken MTL file only loses the colors
+    let materials = materials.unwrap_or_default();
+
+    let mut vertices = Vec::new();
+    let mut indices = Vec::new();
+    for model in models {
+        let obj = model.mesh;
+        let first = vertices.len() as u32;
+        let color = obj
+            .material_id
+            .and_then(|id| materials.get(id))
+            .and_then(|material| material.diffuse)
+            .map_or(DEFAULT_COLOR, Vec3::from);
+
+        // Given for every vertex, or smoothed (also when only some faces have them)
+        let normals = if obj.normals.len() == obj.positions.len() {
+            obj.normals
+                .chunks_exact(3)
+                .map(|normal| Vec3::from_slice(normal).normalize_or_zero())
+                .collect()
+        } else {
+            let positions: Vec<Vec3> = obj
+                .positions
+                .chunks_exact(3)
+                .map(Vec3::from_slice)
+                .collect();
+            smooth_normals(&positions, &obj.indices)
+        };
+        for (idx, position) in obj.positions.chunks_exact(3).enumerate() {
+            // OBJ textures start bottom-left, Vulkan's top-left
+            let uv = obj
+                .texcoords
+                .get(2 * idx..2 * idx + 2)
+                .map_or(Vec2::ZERO, |uv| Vec2::new(uv[0], 1. - uv[1]));
+            vertices.push(Vertex::new(
+                Vec3::from_slice(position),
+                color,
+                normals.get(idx).copied().unwrap_or(Vec3::ZERO),
+                uv,
+            ));
+        }
+        indices.extend(obj.indices.iter().map(|idx| first + idx));
+    }
+
+    Ok(Mesh { vertices, indices })
+}
@@ -0,0 +1,270 @@
+// Deterministic generators of unit primitives (the object's transform scales and places them)
+//
+// Front faces are counter-clockwise seen from outside. Surfaces are built as a (u, v) grid
+// whose position grows with u then v so that dP/du x dP/dv points outward.
+
+use std::{
+    collections::HashMap,
+    f32::consts::{PI, TAU},
+};
+
+use glam::{Vec2, Vec3};
+
+use super::{Mesh, Vertex};
+
+// Corners closer than this make a degenerate triangle (grid rows collapsing on a pole)
+const DEGENERATE_EPSILON: f32 = 1e-6;
+
+#[derive(Default)]
+struct MeshBuilder {
+    vertices: Vec<Vertex>,
+    indices: Vec<u32>,
+}
+
+impl MeshBuilder {
+    fn push_vertex(&mut self, pos: Vec3, normal: Vec3, uv: Vec2) -> u32 {
+        self.vertices
+            .push(Vertex::new(pos, color_of(normal), normal, uv));
+        self.vertices.len() as u32 - 1
+    }
+
+    fn push_triangle(&mut self, a: u32, b: u32, c: u32) {
+        let [pa, pb, pc] = [a, b, c].map(|idx| self.vertices[idx as usize].pos);
+        let collapsed = |p: Vec3, q: Vec3| p.distance_squared(q) < DEGENERATE_EPSILON;
+        if !(collapsed(pa, pb) || collapsed(pb, pc) || collapsed(pc, pa)) {
+            self.indices.extend([a, b, c]);
+        }
+    }
+
+    // (columns + 1) x (rows + 1) vertices from "surface(u, v) -> (position, normal)",
+    // seams and poles are duplicated so each vertex keeps its own uv
+    fn push_grid(&mut self, columns: u32, rows: u32, surface: impl Fn(f32, f32) -> (Vec3, Vec3)) {
+        let first = self.vertices.len() as u32;
+        for row in 0..=rows {
+            for column in 0..=columns {
+                let uv = Vec2::new(column as f32 / columns as f32, row as f32 / rows as f32);
+                let (pos, normal) = surface(uv.x, uv.y);
+                self.push_vertex(pos, normal, uv);
+            }
+        }
+        let idx = |column: u32, row: u32| first + row * (columns + 1) + column;
+        for row in 0..rows {
+            for column in 0..columns {
+                let a = idx(column, row);
+                let b = idx(column + 1, row);
+                let c = idx(column + 1, row + 1);
+                let d = idx(column, row + 1);
+                self.push_triangle(a, b, c);
+                self.push_triangle(a, c, d);
+            }
+        }
+    }
+
+    // Flat disc of radius 1 at height "y" facing "normal" (+Y or -Y)
+    fn push_cap(&mut self, segments: u32, y: f32, normal: Vec3) {
+        let center = self.push_vertex(Vec3::new(0., y, 0.), normal, Vec2::splat(0.5));
+        let rim: Vec<u32> = (0..=segments)
+            .map(|segment| {
+                let (sin, cos) = (TAU * segment as f32 / segments as f32).sin_cos();
+                let uv = Vec2::new(0.5 + cos / 2., 0.5 + sin / 2.);
+                self.push_vertex(Vec3::new(cos, y, sin), normal, uv)
+            })
+            .collect();
+        for pair in rim.windows(2) {
+            if normal.y > 0. {
+                self.push_triangle(center, pair[1], pair[0]);
+            } else {
+                self.push_triangle(center, pair[0], pair[1]);
+            }
+        }
+    }
+
+    fn build(self) -> Mesh {
+        Mesh {
+            vertices: self.vertices,
+            indices: self.indices,
+        }
+    }
+}
+
+// Primitives have no material yet : color them by their normal
+fn color_of(normal: Vec3) -> Vec3 {
+    normal * 0.5 + 0.5
+}
+
+// Point of the unit sphere at longitude u and latitude v (both in 0..1, v = 0 is the top pole)
+fn sphere_point(u: f32, v: f32) -> Vec3 {
+    let (sin_phi, cos_phi) = (TAU * u).sin_cos();
+    let (sin_theta, cos_theta) = (PI * v).sin_cos();
+    Vec3::new(sin_theta * cos_phi, cos_theta, sin_theta * sin_phi)
+}
+
+// Equirectangular mapping of a unit direction
+pub fn sphere_uv(direction: Vec3) -> Vec2 {
+    Vec2::new(
+        0.5 + direction.z.atan2(direction.x) / TAU,
+        direction.y.clamp(-1., 1.).acos() / PI,
+    )
+}
+
+pub fn uv_sphere(rings: u32, segments: u32) -> Mesh {
+    let mut builder = MeshBuilder::default();
+    builder.push_grid(segments, rings, |u, v| {
+        let point = sphere_point(u, v);
+        (point, point)
+    });
+    builder.build()
+}
+
+pub fn icosphere(subdivisions: u32) -> Mesh {
+    let t = (1. + 5f32.sqrt()) / 2.;
+    let mut positions: Vec<Vec3> = [
+        (-1., t, 0.),
+        (1., t, 0.),
+        (-1., -t, 0.),
+        (1., -t, 0.),
+        (0., -1., t),
+        (0., 1., t),
+        (0., -1., -t),
+        (0., 1., -t),
+        (t, 0., -1.),
+        (t, 0., 1.),
+        (-t, 0., -1.),
+        (-t, 0., 1.),
+    ]
+    .into_iter()
+    .map(|(x, y, z)| Vec3::new(x, y, z).normalize())
+    .collect();
+    let mut faces: Vec<[u32; 3]> = vec![
+        [0, 11, 5],
+        [0, 5, 1],
+        [0, 1, 7],
+        [0, 7, 10],
+        [0, 10, 11],
+        [1, 5, 9],
+        [5, 11, 4],
+        [11, 10, 2],
+        [10, 7, 6],
+        [7, 1, 8],
+        [3, 9, 4],
+        [3, 4, 2],
+        [3, 2, 6],
+        [3, 6, 8],
+        [3, 8, 9],
+        [4, 9, 5],
+        [2, 4, 11],
+        [6, 2, 10],
+        [8, 6, 7],
+        [9, 8, 1],
+    ];
+
+    // Each triangle is split in 4, edge midpoints are shared between neighbours
+    for _ in 0..subdivisions {
+        let mut midpoints: HashMap<(u32, u32), u32> = HashMap::new();
+        let mut midpoint = |a: u32, b: u32| {
+            *midpoints.entry((a.min(b), a.max(b))).or_insert_with(|| {
+                let point = (positions[a as usize] + positions[b as usize]).normalize();
+                positions.push(point);
+                positions.len() as u32 - 1
+            })
+        };
+        faces = faces
+            .into_iter()
+            .flat_map(|[a, b, c]| {
+                let ab = midpoint(a, b);
+                let bc = midpoint(b, c);
+                let ca = midpoint(c, a);
+                [[a, ab, ca], [b, bc, ab], [c, ca, bc], [ab, bc, ca]]
+            })
+            .collect();
+    }
+
+    let mut builder = MeshBuilder::default();
+    for position in positions {
+        builder.push_vertex(position, position, sphere_uv(position));
+    }
+    for [a, b, c] in faces {
+        builder.push_triangle(a, b, c);
+    }
+    builder.build()
+}
+
+pub fn cylinder(segments: u32) -> Mesh {
+    let mut builder = MeshBuilder::default();
+    builder.push_grid(segments, 1, |u, v| {
+        let (sin, cos) = (TAU * u).sin_cos();
+        (Vec3::new(cos, 1. - 2. * v, sin), Vec3::new(cos, 0., sin))
+    });
+    builder.push_cap(segments, 1., Vec3::Y);
+    builder.push_cap(segments, -1., -Vec3::Y);
+    builder.build()
+}
+
+pub fn cone(segments: u32) -> Mesh {
+    let mut builder = MeshBuilder::default();
+    // Side normals lean up : the slope rises 2 for 1 inward
+    let side_normal = |angle: f32| {
+        let (sin, cos) = angle.sin_cos();
+        Vec3::new(2. * cos, 1., 2. * sin).normalize()
+    };
+    let base: Vec<u32> = (0..=segments)
+        .map(|segment| {
+            let u = segment as f32 / segments as f32;
+            let (sin, cos) = (TAU * u).sin_cos();
+            builder.push_vertex(
+                Vec3::new(cos, -1., sin),
+                side_normal(TAU * u),
+                Vec2::new(u, 1.),
+            )
+        })
+        .collect();
+    // One apex per slice so each gets the normal of its slice
+    for segment in 0..segments {
+        let u = (segment as f32 + 0.5) / segments as f32;
+        let apex = builder.push_vertex(Vec3::Y, side_normal(TAU * u), Vec2::new(u, 0.));
+        builder.push_triangle(base[segment as usize], apex, base[segment as usize + 1]);
+    }
+    builder.push_cap(segments, -1., -Vec3::Y);
+    builder.build()
+}
+
+pub fn torus(rings: u32, segments: u32, thickness: f32) -> Mesh {
+    let mut builder = MeshBuilder::default();
+    builder.push_grid(rings, segments, |u, v| {
+        let (sin_phi, cos_phi) = (TAU * u).sin_cos();
+        let (sin_psi, cos_psi) = (TAU * v).sin_cos();
+        let normal = Vec3::new(cos_psi * cos_phi, -sin_psi, cos_psi * sin_phi);
+        let center = Vec3::new(cos_phi, 0., sin_phi);
+        (center + thickness * normal, normal)
+    });
+    builder.build()
+}
+
+pub fn plane(subdivisions: u32) -> Mesh {
+    let mut builder = MeshBuilder::default();
+    builder.push_grid(subdivisions, subdivisions, |u, v| {
+        (Vec3::new(2. * u - 1., 0., 1. - 2. * v), Vec3::Y)
+    });
+    builder.build()
+}
+
+// A sphere cut at the equator whose halves are moved apart by "length"
+pub fn capsule(rings: u32, segments: u32, length: f32) -> Mesh {
+    let mut builder = MeshBuilder::default();
+    for (top, offset) in [(true, length / 2.), (false, -length / 2.)] {
+        builder.push_grid(segments, rings, |u, v| {
+            let point = sphere_point(u, if top { v / 2. } else { 0.5 + v / 2. });
+            (point + offset * Vec3::Y, point)
+        });
+    }
+    // Cylinder between the hemispheres' equators
+    let equator = |y: f32| {
+        move |u: f32| {
+            let (sin, cos) = (TAU * u).sin_cos();
+            (Vec3::new(cos, y, sin), Vec3::new(cos, 0., sin))
+        }
+    };
+    let (top, bottom) = (equator(length / 2.), equator(-length / 2.));
+    builder.push_grid(segments, 1, |u, v| if v < 0.5 { top(u) } else { bottom(u) });
+    builder.build()
+}
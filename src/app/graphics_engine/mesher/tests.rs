@@ -0,0 +1,617 @@
+// Shared checks for ToMesh implementations, they only look at the CPU-side mesh (no GPU needed)
+//
+// Winding : the projection flips Y to Vulkan's clip space and the pipeline uses
+// FrontFace::COUNTER_CLOCKWISE, so front faces are triangles that are counter-clockwise
+// seen from outside (right-hand normal pointing out)
+
+use std::{collections::HashMap, path::PathBuf};
+
+use glam::{Quat, Vec3};
+
+use crate::app::{
+    model::{
+        object::{
+            Capsule, Cone, Cube, Cylinder, Icosphere, MeshObject, Octahedron, Plane, Torus,
+            UvSphere,
+        },
+        space::Coord,
+    },
+    temp_dir::TempDir,
+};
+
+use super::{load_gltf, load_obj, Mesh, ToMesh};
+
+// Positions closer than this are the same point (seams duplicate vertices)
+const WELD_EPSILON: f32 = 1e-4;
+
+type Point = (i64, i64, i64);
+
+fn weld(position: Vec3) -> Point {
+    let quantized = (position / WELD_EPSILON).round();
+    (quantized.x as i64, quantized.y as i64, quantized.z as i64)
+}
+
+fn triangles(mesh: &Mesh) -> impl Iterator<Item = [Vec3; 3]> + '_ {
+    mesh.indices.chunks_exact(3).map(|triangle| {
+        [
+            mesh.vertices[triangle[0] as usize].pos,
+            mesh.vertices[triangle[1] as usize].pos,
+            mesh.vertices[triangle[2] as usize].pos,
+        ]
+    })
+}
+
+pub fn triangle_count(mesh: &Mesh) -> usize {
+    mesh.indices.len() / 3
+}
+
+pub fn distinct_positions(mesh: &Mesh) -> usize {
+    let mut points: Vec<Point> = mesh
+        .vertices
+        .iter()
+        .map(|vertex| weld(vertex.pos))
+        .collect();
+    points.sort();
+    points.dedup();
+    points.len()
+}
+
+// Valid triangle list : complete triangles, indices in bounds, no degenerate triangle
+pub fn check_topology(mesh: &Mesh) {
+    assert!(!mesh.indices.is_empty(), "Mesh has no triangle.");
+    assert_eq!(mesh.indices.len() % 3, 0, "Indices aren't a triangle list.");
+    for index in &mesh.indices {
+        assert!(
+            (*index as usize) < mesh.vertices.len(),
+            "Index {index} is out of bounds ({} vertices).",
+            mesh.vertices.len()
+        );
+    }
+    for [a, b, c] in triangles(mesh) {
+        let area = (b - a).cross(c - a).length() / 2.;
+        assert!(area > WELD_EPSILON * WELD_EPSILON, "Degenerate triangle.");
+    }
+}
+
+// Watertight with consistent winding : each edge a -> b is matched by exactly one b -> a
+pub fn check_closed(mesh: &Mesh) {
+    let mut edges: HashMap<(Point, Point), i32> = HashMap::new();
+    for [a, b, c] in triangles(mesh) {
+        let points = [weld(a), weld(b), weld(c)];
+        for (a, b) in [
+            (points[0], points[1]),
+            (points[1], points[2]),
+            (points[2], points[0]),
+        ] {
+            *edges.entry((a, b)).or_default() += 1;
+        }
+    }
+    for ((a, b), count) in &edges {
+        assert_eq!(
+            *count, 1,
+            "Edge {a:?} -> {b:?} is shared by {count} triangles."
+        );
+        assert_eq!(
+            edges.get(&(*b, *a)),
+            Some(&1),
+            "Edge {a:?} -> {b:?} has no opposite : mesh is open or inconsistently wound."
+        );
+    }
+}
+
+// Every triangle faces away from "center" (meshes that are star-shaped around it)
+pub fn check_outward_winding(mesh: &Mesh, center: Vec3) {
+    for [a, b, c] in triangles(mesh) {
+        let normal = (b - a).cross(c - a);
+        let centroid = (a + b + c) / 3.;
+        assert!(
+            normal.dot(centroid - center) > 0.,
+            "Triangle {a} {b} {c} is wound inward (culled as a back face)."
+        );
+    }
+}
+
+// Positive enclosed volume : closed meshes wound outward even when not star-shaped (torus)
+pub fn check_outward_volume(mesh: &Mesh) {
+    let volume: f32 = triangles(mesh)
+        .map(|[a, b, c]| a.dot(b.cross(c)) / 6.)
+        .sum();
+    assert!(volume > 0., "Mesh is wound inward (volume {volume}).");
+}
+
+fn origin() -> Coord {
+    Coord::new(0., 0., 0.)
+}
+
+#[test]
+fn octahedron_mesh() {
+    let mesh = Octahedron::new_unoriented(Coord::new(0., 0., 0.), 1.).mesh();
+    check_topology(&mesh);
+    assert_eq!(mesh.vertices.len(), 6);
+    assert_eq!(triangle_count(&mesh), 8);
+    check_closed(&mesh);
+    check_outward_winding(&mesh, Vec3::ZERO);
+}
+
+#[test]
+fn cube_mesh() {
+    let mesh = Cube::new_unoriented(Coord::new(0., 0., 0.), 1.).mesh();
+    check_topology(&mesh);
+    assert_eq!(mesh.vertices.len(), 24);
+    assert_eq!(triangle_count(&mesh), 12);
+    assert_eq!(distinct_positions(&mesh), 8);
+    check_closed(&mesh);
+    check_outward_winding(&mesh, Vec3::ZERO);
+}
+
+#[test]
+fn uv_sphere_mesh() {
+    let mesh = UvSphere::new_unoriented(origin(), 1., 8, 16).mesh();
+    check_topology(&mesh);
+    assert_eq!(mesh.vertices.len(), 9 * 17);
+    // Quads touching the poles are single triangles
+    assert_eq!(triangle_count(&mesh), 2 * 16 * (8 - 1));
+    assert_eq!(distinct_positions(&mesh), 16 * (8 - 1) + 2);
+    check_closed(&mesh);
+    check_outward_winding(&mesh, Vec3::ZERO);
+}
+
+#[test]
+fn icosphere_mesh() {
+    for subdivisions in 0..3 {
+        let mesh = Icosphere::new_unoriented(origin(), 1., subdivisions).mesh();
+        check_topology(&mesh);
+        assert_eq!(mesh.vertices.len(), 10 * 4usize.pow(subdivisions) + 2);
+        assert_eq!(triangle_count(&mesh), 20 * 4usize.pow(subdivisions));
+        check_closed(&mesh);
+        check_outward_winding(&mesh, Vec3::ZERO);
+    }
+}
+
+#[test]
+fn cylinder_mesh() {
+    let mesh = Cylinder::new_unoriented(origin(), 1., 12).mesh();
+    check_topology(&mesh);
+    // Side (seam duplicated) then two caps with their centers
+    assert_eq!(mesh.vertices.len(), 2 * 13 + 2 * (13 + 1));
+    assert_eq!(triangle_count(&mesh), 4 * 12);
+    assert_eq!(distinct_positions(&mesh), 2 * 12 + 2);
+    check_closed(&mesh);
+    check_outward_winding(&mesh, Vec3::ZERO);
+}
+
+#[test]
+fn cone_mesh() {
+    let mesh = Cone::new_unoriented(origin(), 1., 12).mesh();
+    check_topology(&mesh);
+    // Base ring, one apex per slice, then the base cap
+    assert_eq!(mesh.vertices.len(), 13 + 12 + (13 + 1));
+    assert_eq!(triangle_count(&mesh), 2 * 12);
+    assert_eq!(distinct_positions(&mesh), 12 + 2);
+    check_closed(&mesh);
+    check_outward_winding(&mesh, Vec3::ZERO);
+}
+
+#[test]
+fn torus_mesh() {
+    let mesh = Torus::new_unoriented(origin(), 1., 16, 8, 0.25).mesh();
+    check_topology(&mesh);
+    assert_eq!(mesh.vertices.len(), 17 * 9);
+    assert_eq!(triangle_count(&mesh), 2 * 16 * 8);
+    assert_eq!(distinct_positions(&mesh), 16 * 8);
+    check_closed(&mesh);
+    check_outward_volume(&mesh);
+}
+
+#[test]
+fn plane_mesh() {
+    let mesh = Plane::new_unoriented(origin(), 1., 4).mesh();
+    check_topology(&mesh);
+    assert_eq!(mesh.vertices.len(), 5 * 5);
+    assert_eq!(triangle_count(&mesh), 2 * 4 * 4);
+    // Open surface facing +Y
+    check_outward_winding(&mesh, -Vec3::Y);
+}
+
+#[test]
+fn capsule_mesh() {
+    let mesh = Capsule::new_unoriented(origin(), 1., 4, 16, 2.).mesh();
+    check_topology(&mesh);
+    // Two hemispheres then the cylinder between them
+    assert_eq!(mesh.vertices.len(), 2 * 5 * 17 + 2 * 17);
+    assert_eq!(triangle_count(&mesh), 2 * (2 * 16 * 4 - 16) + 2 * 16);
+    assert_eq!(distinct_positions(&mesh), 2 * 16 * 4 + 2);
+    check_closed(&mesh);
+    check_outward_winding(&mesh, Vec3::ZERO);
+}
+
+#[test]
+fn primitives_are_deterministic() {
+    let meshes = || {
+        [
+            UvSphere::new_unoriented(origin(), 1., 8, 16).mesh(),
+            Icosphere::new_unoriented(origin(), 1., 2).mesh(),
+            Torus::new_unoriented(origin(), 1., 16, 8, 0.25).mesh(),
+            Capsule::new_unoriented(origin(), 1., 4, 16, 2.).mesh(),
+        ]
+    };
+    for (first, second) in meshes().iter().zip(meshes().iter()) {
+        assert_eq!(first.indices, second.indices);
+        let positions = |mesh: &Mesh| -> Vec<[f32; 8]> {
+            mesh.vertices
+                .iter()
+                .map(|vertex| {
+                    let (pos, normal, uv) = (vertex.pos, vertex.normal, vertex.uv);
+                    [
+                        pos.x, pos.y, pos.z, normal.x, normal.y, normal.z, uv.x, uv.y,
+                    ]
+                })
+                .collect()
+        };
+        assert_eq!(positions(first), positions(second));
+    }
+}
+
+// Writes "files" (name, content) in "dir", returns the first file's path
+fn write_files(dir: &TempDir, files: &[(&str, &[u8])]) -> PathBuf {
+    for (name, content) in files {
+        dir.write(name, content);
+    }
+    dir.path().join(files[0].0)
+}
+
+// Cube with shared positions but one normal and uv set per face
+const CUBE_OBJ: &str = "
+mtllib cube.mtl
+v -1 -1 -1
+v 1 -1 -1
+v 1 1 -1
+v -1 1 -1
+v -1 -1 1
+v 1 -1 1
+v 1 1 1
+v -1 1 1
+vn 1 0 0
+vn -1 0 0
+vn 0 1 0
+vn 0 -1 0
+vn 0 0 1
+vn 0 0 -1
+vt 0 0
+vt 1 0
+vt 1 1
+vt 0 1
+usemtl red
+f 2/1/1 3/2/1 7/3/1 6/4/1
+f 1/1/2 5/2/2 8/3/2 4/4/2
+f 4/1/3 8/2/3 7/3/3 3/4/3
+f 1/1/4 2/2/4 6/3/4 5/4/4
+f 5/1/5 6/2/5 7/3/5 8/4/5
+f 1/1/6 4/2/6 3/3/6 2/4/6
+";
+
+const CUBE_MTL: &str = "
+newmtl red
+Kd 1 0 0
+";
+
+#[test]
+fn obj_cube() {
+    let dir = TempDir::new("obj_cube");
+    let path = write_files(
+        &dir,
+        &[
+            ("cube.obj", CUBE_OBJ.as_bytes()),
+            ("cube.mtl", CUBE_MTL.as_bytes()),
+        ],
+    );
+    let mesh = load_obj(&path).expect("Failed to load OBJ.");
+    check_topology(&mesh);
+    // Quads are triangulated, each corner of each face is its own vertex
+    assert_eq!(mesh.vertices.len(), 24);
+    assert_eq!(triangle_count(&mesh), 12);
+    assert_eq!(distinct_positions(&mesh), 8);
+    check_closed(&mesh);
+    check_outward_winding(&mesh, Vec3::ZERO);
+    for vertex in &mesh.vertices {
+        assert_eq!(vertex.color, Vec3::X);
+        // Given normals are kept : they point out of their face
+        assert!(vertex.normal.dot(vertex.pos) > 0.);
+    }
+}
+
+#[test]
+fn obj_shared_vertices() {
+    // Octahedron without normals, uvs nor material : positions are shared between faces
+    let obj = "
+v 1 0 0
+v -1 0 0
+v 0 1 0
+v 0 -1 0
+v 0 0 1
+v 0 0 -1
+f 1 3 5
+f 1 5 4
+f 1 4 6
+f 1 6 3
+f 2 5 3
+f 2 4 5
+f 2 6 4
+f 2 3 6
+";
+    let dir = TempDir::new("obj_shared_vertices");
+    let path = write_files(&dir, &[("octahedron.obj", obj.as_bytes())]);
+    let mesh = load_obj(&path).expect("Failed to load OBJ.");
+    check_topology(&mesh);
+    assert_eq!(mesh.vertices.len(), 6);
+    assert_eq!(triangle_count(&mesh), 8);
+    check_closed(&mesh);
+    check_outward_winding(&mesh, Vec3::ZERO);
+    for vertex in &mesh.vertices {
+        // Smoothed normals of a symmetric shape point along the position, default color is white
+        assert!(vertex.normal.abs_diff_eq(vertex.pos, 1e-5));
+        assert_eq!(vertex.color, Vec3::ONE);
+    }
+}
+
+#[test]
+fn obj_partial_normals() {
+    // Normals given for one face only : all smoothed instead
+    let obj = "
+v 0 0 0
+v 1 0 0
+v 0 1 0
+v 0 0 1
+vn 0 0 -1
+f 1//1 3//1 2//1
+f 1 2 4
+f 1 4 3
+f 2 3 4
+";
+    let dir = TempDir::new("obj_partial_normals");
+    let path = write_files(&dir, &[("tetrahedron.obj", obj.as_bytes())]);
+    let mesh = load_obj(&path).expect("Failed to load OBJ.");
+    check_topology(&mesh);
+    assert_eq!(triangle_count(&mesh), 4);
+    for vertex in &mesh.vertices {
+        assert!((vertex.normal.length() - 1.).abs() < 1e-5);
+    }
+}
+
+#[test]
+fn obj_missing_file() {
+    assert!(load_obj(&std::env::temp_dir().join("vulkan_test_missing.obj")).is_err());
+}
+
+// glTF samples : a unit quad facing +Z (positions, normals, uvs, u16 indices) in one buffer
+const QUAD_BUFFER_LENGTH: usize = 140;
+
+fn quad_buffer() -> Vec<u8> {
+    let positions = [[-1., -1., 0.], [1., -1., 0.], [1., 1., 0.], [-1., 1., 0.]];
+    let normals = [[0., 0., 1.]; 4];
+    // Left then right texel of the sample texture
+    let uvs = [[0.25, 0.5], [0.75, 0.5], [0.75, 0.5], [0.25, 0.5]];
+    let indices: [u16; 6] = [0, 1, 2, 0, 2, 3];
+    let mut buffer = Vec::new();
+    for float in positions
+        .iter()
+        .chain(&normals)
+        .flatten()
+        .chain(uvs.iter().flatten())
+    {
+        buffer.extend(f32::to_le_bytes(*float));
+    }
+    for index in indices {
+        buffer.extend(index.to_le_bytes());
+    }
+    buffer.resize(QUAD_BUFFER_LENGTH, 0);
+    buffer
+}
+
+// Everything but "buffers", "images", "textures", "materials" and the scene graph
+const QUAD_GLTF: &str = r#"
+    "asset": { "version": "2.0" },
+    "bufferViews": [
+        { "buffer": 0, "byteOffset": 0, "byteLength": 48 },
+        { "buffer": 0, "byteOffset": 48, "byteLength": 48 },
+        { "buffer": 0, "byteOffset": 96, "byteLength": 32 },
+        { "buffer": 0, "byteOffset": 128, "byteLength": 12 }
+    ],
+    "accessors": [
+        { "bufferView": 0, "componentType": 5126, "count": 4, "type": "VEC3",
+          "min": [-1, -1, 0], "max": [1, 1, 0] },
+        { "bufferView": 1, "componentType": 5126, "count": 4, "type": "VEC3" },
+        { "bufferView": 2, "componentType": 5126, "count": 4, "type": "VEC2" },
+        { "bufferView": 3, "componentType": 5123, "count": 6, "type": "SCALAR" }
+    ],
+    "meshes": [{ "primitives": [{
+        "attributes": { "POSITION": 0, "NORMAL": 1, "TEXCOORD_0": 2 },
+        "indices": 3,
+        "material": 0
+    }] }]
+"#;
+
+// 2x1 PNG : a green texel then a blue one
+const TEXTURE_PNG: &str = "data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAAAIAAAABCAIAAAB7QOjdAAAAD0lEQVR4nGNg+M/AwPAfAAYBAf9d/01yAAAAAElFTkSuQmCC";
+
+fn assert_vec3_eq(actual: Vec3, expected: Vec3) {
+    assert!(
+        actual.abs_diff_eq(expected, 1e-5),
+        "{actual} isn't {expected}."
+    );
+}
+
+#[test]
+fn gltf_node_hierarchy() {
+    // Parent : moved, turned a quarter around Y and scaled by 2, without mesh
+    // Child : moved up and stretched along Y (non-uniform), with the quad
+    // Second root : moved back and halved, with the quad
+    let gltf = format!(
+        r#"{{
+    {QUAD_GLTF},
+    "buffers": [{{ "uri": "quad.bin", "byteLength": {QUAD_BUFFER_LENGTH} }}],
+    "materials": [{{ "pbrMetallicRoughness": {{ "baseColorFactor": [1, 0, 0, 1] }} }}],
+    "nodes": [
+        {{ "children": [1], "translation": [1, 0, 0],
+           "rotation": [0, 0.70710678, 0, 0.70710678], "scale": [2, 2, 2] }},
+        {{ "mesh": 0, "translation": [0, 1, 0], "scale": [1, 2, 1] }},
+        {{ "mesh": 0, "translation": [0, 0, -3], "scale": [0.5, 0.5, 0.5] }}
+    ],
+    "scenes": [{{ "nodes": [0, 2] }}],
+    "scene": 0
+}}"#
+    );
+    let dir = TempDir::new("gltf_node_hierarchy");
+    let path = write_files(
+        &dir,
+        &[
+            ("scene.gltf", gltf.as_bytes()),
+            ("quad.bin", &quad_buffer()),
+        ],
+    );
+    let objects = load_gltf(&path).expect("Failed to load glTF.");
+    assert_eq!(objects.len(), 2);
+
+    // Child : parent's transform applied to its own, its stretch baked in the vertices
+    let child = &objects[0];
+    assert_vec3_eq(child.position.to_vec3(), Vec3::new(1., 2., 0.));
+    assert!(child
+        .orientation
+        .to_quat()
+        .abs_diff_eq(Quat::from_rotation_y(std::f32::consts::FRAC_PI_2), 1e-5));
+    assert_eq!(child.size, 1.);
+    assert_vec3_eq(child.mesh().vertices[2].pos, Vec3::new(2., 4., 0.));
+    assert_vec3_eq(child.mesh().vertices[2].normal, Vec3::Z);
+
+    let second = &objects[1];
+    assert_vec3_eq(second.position.to_vec3(), Vec3::new(0., 0., -3.));
+    assert!(second
+        .orientation
+        .to_quat()
+        .abs_diff_eq(Quat::IDENTITY, 1e-5));
+    assert_eq!(second.size, 0.5);
+    assert_vec3_eq(second.mesh().vertices[2].pos, Vec3::new(1., 1., 0.));
+
+    for object in &objects {
+        let mesh = &object.mesh();
+        check_topology(mesh);
+        assert_eq!(mesh.vertices.len(), 4);
+        assert_eq!(triangle_count(mesh), 2);
+        check_outward_winding(mesh, -Vec3::Z);
+        // The material colors the vertices when drawn
+        assert_eq!(object.material().base_color, Vec3::X);
+        assert!(object.material().texture.is_none());
+        for vertex in &mesh.vertices {
+            assert_eq!(vertex.color, Vec3::ONE);
+        }
+    }
+}
+
+#[test]
+fn gltf_sheared_node() {
+    // x moved along y by its value : doesn't split into a scale and a rotation
+    let gltf = format!(
+        r#"{{
+    {QUAD_GLTF},
+    "buffers": [{{ "uri": "quad.bin", "byteLength": {QUAD_BUFFER_LENGTH} }}],
+    "materials": [{{}}],
+    "nodes": [{{ "mesh": 0, "matrix": [1, 1, 0, 0, 0, 1, 0, 0, 0, 0, 1, 0, 2, 0, 0, 1] }}],
+    "scenes": [{{ "nodes": [0] }}]
+}}"#
+    );
+    let dir = TempDir::new("gltf_sheared_node");
+    let path = write_files(
+        &dir,
+        &[
+            ("scene.gltf", gltf.as_bytes()),
+            ("quad.bin", &quad_buffer()),
+        ],
+    );
+    let objects = load_gltf(&path).expect("Failed to load glTF.");
+    let object = &objects[0];
+    assert_vec3_eq(object.position.to_vec3(), Vec3::new(2., 0., 0.));
+    assert!(object
+        .orientation
+        .to_quat()
+        .abs_diff_eq(Quat::IDENTITY, 1e-5));
+    assert_eq!(object.size, 1.);
+    // The whole linear part in the vertices
+    let mesh = object.mesh();
+    assert_vec3_eq(mesh.vertices[1].pos, Vec3::new(1., 0., 0.));
+    assert_vec3_eq(mesh.vertices[2].pos, Vec3::new(1., 2., 0.));
+    assert_vec3_eq(mesh.vertices[2].normal, Vec3::Z);
+    check_outward_winding(&mesh, -Vec3::Z);
+}
+
+#[test]
+fn glb_textured() {
+    let json = format!(
+        r#"{{
+    {QUAD_GLTF},
+    "buffers": [{{ "byteLength": {QUAD_BUFFER_LENGTH} }}],
+    "images": [{{ "uri": "{TEXTURE_PNG}" }}],
+    "textures": [{{ "source": 0 }}],
+    "materials": [{{ "pbrMetallicRoughness": {{
+        "baseColorFactor": [0.5, 1, 1, 1],
+        "baseColorTexture": {{ "index": 0 }}
+    }} }}],
+    "nodes": [{{ "mesh": 0 }}],
+    "scenes": [{{ "nodes": [0] }}]
+}}"#
+    );
+    // GLB : header then JSON and BIN chunks, each padded to 4 bytes
+    let mut json = json.into_bytes();
+    json.resize(json.len().next_multiple_of(4), b' ');
+    let bin = quad_buffer();
+    let mut glb = Vec::new();
+    glb.extend(b"glTF");
+    glb.extend(2u32.to_le_bytes());
+    glb.extend((12 + 8 + json.len() as u32 + 8 + bin.len() as u32).to_le_bytes());
+    glb.extend((json.len() as u32).to_le_bytes());
+    glb.extend(b"JSON");
+    glb.extend(&json);
+    glb.extend((bin.len() as u32).to_le_bytes());
+    glb.extend(b"BIN\0");
+    glb.extend(&bin);
+
+    let dir = TempDir::new("glb_textured");
+    let path = write_files(&dir, &[("quad.glb", &glb)]);
+    let objects = load_gltf(&path).expect("Failed to load GLB.");
+    assert_eq!(objects.len(), 1);
+    let object = &objects[0];
+    assert_vec3_eq(object.position.to_vec3(), Vec3::ZERO);
+    assert_eq!(object.size, 1.);
+
+    // Base color factor, and the texture sampled at the uvs when drawn
+    let mesh = &object.mesh();
+    check_topology(mesh);
+    assert_eq!(mesh.vertices.len(), 4);
+    assert_eq!(triangle_count(mesh), 2);
+    assert_eq!(mesh.vertices[2].uv, glam::Vec2::new(0.75, 0.5));
+    let material = object.material();
+    assert_vec3_eq(material.base_color, Vec3::new(0.5, 1., 1.));
+    let texture = material.texture.expect("Texture not loaded.");
+    assert_eq!((texture.width, texture.height), (2, 1));
+    assert_eq!(&texture.pixels[..], [0, 255, 0, 255, 0, 0, 255, 255]);
+    // Objects sharing the file share its texture
+    let copy = object.clone();
+    assert_eq!(copy.material().texture.unwrap().key, texture.key);
+}
+
+#[test]
+fn mesh_keys() {
+    // Same shape and parameters share their geometry, wherever and however big they are
+    let torus = |thickness| Torus::new_unoriented(origin(), 1., 16, 8, thickness);
+    let moved = Torus::new_unoriented(Coord::new(1., 2., 3.), 4., 16, 8, 0.25);
+    assert_eq!(torus(0.25).mesh_key(), moved.mesh_key());
+    assert_ne!(torus(0.25).mesh_key(), torus(0.5).mesh_key());
+    assert_ne!(
+        Cylinder::new_unoriented(origin(), 1., 12).mesh_key(),
+        Cone::new_unoriented(origin(), 1., 12).mesh_key()
+    );
+    // Given meshes never share a key unless told so
+    let mesh = Cube::new_unoriented(origin(), 1.).mesh();
+    let first = MeshObject::new_unoriented(origin(), 1., mesh.clone());
+    let second = MeshObject::new_unoriented(origin(), 1., mesh);
+    assert_ne!(first.mesh_key(), second.mesh_key());
+}
@@ -1,24 +1,41 @@
-use glam::{Mat4, Vec3};
+use glam::{Mat4, Vec2, Vec3};
 
-use crate::app::model::object::{Cube, Octahedron};
+use crate::app::model::{
+    object::{
+        Capsule, Cone, Cube, Cylinder, Icosphere, MeshObject, Octahedron, Placed, Plane, Torus,
+        UvSphere,
+    },
+    space::{Coord, Orientation},
+    world::Renderable,
+};
 
-use super::{Mesh, ToMesh, Vertex};
+use super::{primitives, Material, Mesh, MeshKey, ToMesh, Vertex};
+
+fn transform(position: &Coord, orientation: &Orientation, size: f32) -> Mat4 {
+    Mat4::from_scale_rotation_translation(
+        Vec3::ONE * size,
+        orientation.to_quat(),
+        position.to_vec3(),
+    )
+}
 
 impl ToMesh for Octahedron {
     fn transform(&self) -> Mat4 {
-        glam::Mat4::from_scale_rotation_translation(
-            Vec3::ONE * self.size,
-            self.orientation.to_quat(),
-            self.position.to_vec3(),
-        )
+        transform(&self.position, &self.orientation, self.size)
     }
+    fn mesh_key(&self) -> MeshKey {
+        MeshKey::new("octahedron", [0; 3])
+    }
+    // Vertices are shared between faces : normals point away from the center
     fn mesh(&self) -> Mesh {
-        let top = Vertex::new(Vec3::X, Vec3::X);
-        let bottom = Vertex::new(-Vec3::X, Vec3::X);
-        let right = Vertex::new(Vec3::Y, Vec3::Y);
-        let left = Vertex::new(-Vec3::Y, Vec3::Y);
-        let near = Vertex::new(Vec3::Z, Vec3::Z);
-        let far = Vertex::new(-Vec3::Z, Vec3::Z);
+        let vertex =
+            |pos: Vec3, color: Vec3| Vertex::new(pos, color, pos, primitives::sphere_uv(pos));
+        let top = vertex(Vec3::X, Vec3::X);
+        let bottom = vertex(-Vec3::X, Vec3::X);
+        let right = vertex(Vec3::Y, Vec3::Y);
+        let left = vertex(-Vec3::Y, Vec3::Y);
+        let near = vertex(Vec3::Z, Vec3::Z);
+        let far = vertex(-Vec3::Z, Vec3::Z);
 
         let vertices = vec![top, bottom, right, left, near, far];
         let indices = vec![
@@ -31,25 +48,176 @@ impl ToMesh for Octahedron {
 
 impl ToMesh for Cube {
     fn transform(&self) -> Mat4 {
-        glam::Mat4::from_scale_rotation_translation(
-            Vec3::ONE * self.size,
-            self.orientation.to_quat(),
-            self.position.to_vec3(),
-        )
+        transform(&self.position, &self.orientation, self.size)
+    }
+    fn mesh_key(&self) -> MeshKey {
+        MeshKey::new("cube", [0; 3])
     }
+    // 4 vertices per face (flat colors and normals), 2 triangles per face
     fn mesh(&self) -> Mesh {
-        let top = Vertex::new(Vec3::X, Vec3::X);
-        let bottom = Vertex::new(-Vec3::X, Vec3::X);
-        let right = Vertex::new(Vec3::Y, Vec3::Y);
-        let left = Vertex::new(-Vec3::Y, Vec3::Y);
-        let near = Vertex::new(Vec3::Z, Vec3::Z);
-        let far = Vertex::new(-Vec3::Z, Vec3::Z);
-
-        let vertices = vec![top, bottom, right, left, near, far];
-        let indices = vec![
-            0, 2, 4, 0, 4, 3, 0, 3, 5, 0, 5, 2, 1, 4, 2, 1, 3, 4, 1, 5, 3, 1, 2, 5,
+        // (normal, u, v) with u x v = normal : corners are counter-clockwise seen from outside
+        let faces = [
+            (Vec3::X, Vec3::Y, Vec3::Z),
+            (-Vec3::X, Vec3::Z, Vec3::Y),
+            (Vec3::Y, Vec3::Z, Vec3::X),
+            (-Vec3::Y, Vec3::X, Vec3::Z),
+            (Vec3::Z, Vec3::X, Vec3::Y),
+            (-Vec3::Z, Vec3::Y, Vec3::X),
         ];
 
+        let mut vertices = Vec::with_capacity(24);
+        let mut indices = Vec::with_capacity(36);
+        for (normal, u, v) in faces {
+            let first = vertices.len() as u32;
+            let color = normal.abs();
+            for (corner_u, corner_v) in [(-1., -1.), (1., -1.), (1., 1.), (-1., 1.)] {
+                let pos = normal + corner_u * u + corner_v * v;
+                let uv = Vec2::new(corner_u + 1., corner_v + 1.) / 2.;
+                vertices.push(Vertex::new(pos, color, normal, uv));
+            }
+            indices.extend([first, first + 1, first + 2, first, first + 2, first + 3]);
+        }
+
         Mesh { vertices, indices }
     }
 }
+
+impl ToMesh for UvSphere {
+    fn transform(&self) -> Mat4 {
+        transform(&self.position, &self.orientation, self.size)
+    }
+    fn mesh_key(&self) -> MeshKey {
+        MeshKey::new("uv_sphere", [self.rings, self.segments, 0])
+    }
+    fn mesh(&self) -> Mesh {
+        primitives::uv_sphere(self.rings, self.segments)
+    }
+}
+
+impl ToMesh for Icosphere {
+    fn transform(&self) -> Mat4 {
+        transform(&self.position, &self.orientation, self.size)
+    }
+    fn mesh_key(&self) -> MeshKey {
+        MeshKey::new("icosphere", [self.subdivisions, 0, 0])
+    }
+    fn mesh(&self) -> Mesh {
+        primitives::icosphere(self.subdivisions)
+    }
+}
+
+impl ToMesh for Cylinder {
+    fn transform(&self) -> Mat4 {
+        transform(&self.position, &self.orientation, self.size)
+    }
+    fn mesh_key(&self) -> MeshKey {
+        MeshKey::new("cylinder", [self.segments, 0, 0])
+    }
+    fn mesh(&self) -> Mesh {
+        primitives::cylinder(self.segments)
+    }
+}
+
+impl ToMesh for Cone {
+    fn transform(&self) -> Mat4 {
+        transform(&self.position, &self.orientation, self.size)
+    }
+    fn mesh_key(&self) -> MeshKey {
+        MeshKey::new("cone", [self.segments, 0, 0])
+    }
+    fn mesh(&self) -> Mesh {
+        primitives::cone(self.segments)
+    }
+}
+
+impl ToMesh for Torus {
+    fn transform(&self) -> Mat4 {
+        transform(&self.position, &self.orientation, self.size)
+    }
+    fn mesh_key(&self) -> MeshKey {
+        MeshKey::new(
+            "torus",
+            [self.rings, self.segments, self.thickness.to_bits()],
+        )
+    }
+    fn mesh(&self) -> Mesh {
+        primitives::torus(self.rings, self.segments, self.thickness)
+    }
+}
+
+impl ToMesh for Plane {
+    fn transform(&self) -> Mat4 {
+        transform(&self.position, &self.orientation, self.size)
+    }
+    fn mesh_key(&self) -> MeshKey {
+        MeshKey::new("plane", [self.subdivisions, 0, 0])
+    }
+    fn mesh(&self) -> Mesh {
+        primitives::plane(self.subdivisions)
+    }
+}
+
+impl ToMesh for Capsule {
+    fn transform(&self) -> Mat4 {
+        transform(&self.position, &self.orientation, self.size)
+    }
+    fn mesh_key(&self) -> MeshKey {
+        MeshKey::new(
+            "capsule",
+            [self.rings, self.segments, self.length.to_bits()],
+        )
+    }
+    fn mesh(&self) -> Mesh {
+        primitives::capsule(self.rings, self.segments, self.length)
+    }
+}
+
+impl ToMesh for MeshObject {
+    fn transform(&self) -> Mat4 {
+        transform(&self.position, &self.orientation, self.size)
+    }
+    fn mesh_key(&self) -> MeshKey {
+        self.mesh_key
+    }
+    fn mesh(&self) -> Mesh {
+        self.mesh.clone()
+    }
+    fn material(&self) -> Material {
+        self.material.clone()
+    }
+}
+
+impl ToMesh for Placed<'_> {
+    fn transform(&self) -> Mat4 {
+        self.transform
+    }
+    fn mesh_key(&self) -> MeshKey {
+        self.object.mesh_key()
+    }
+    fn mesh(&self) -> Mesh {
+        self.object.mesh()
+    }
+    fn material(&self) -> Material {
+        self.object.material()
+    }
+}
+
+// The shape, its material tinted
+impl ToMesh for Renderable {
+    fn transform(&self) -> Mat4 {
+        self.shape.transform()
+    }
+    fn mesh_key(&self) -> MeshKey {
+        self.shape.mesh_key()
+    }
+    fn mesh(&self) -> Mesh {
+        self.shape.mesh()
+    }
+    fn material(&self) -> Material {
+        let mut material = self.shape.material();
+        if let Some(tint) = self.tint {
+            material.base_color *= tint.color;
+        }
+        material
+    }
+}
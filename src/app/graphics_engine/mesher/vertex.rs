@@ -3,17 +3,25 @@ use std::mem::offset_of;
 use ash::vk::{
     Format, VertexInputAttributeDescription, VertexInputBindingDescription, VertexInputRate,
 };
-use glam::Vec3;
+use glam::{Vec2, Vec3, Vec4};
 
 #[repr(C)]
+#[derive(Clone, Copy)]
 pub struct Vertex {
     pub pos: Vec3,
     pub color: Vec3,
+    pub normal: Vec3,
+    pub uv: Vec2,
 }
 
 impl Vertex {
-    pub fn new(pos: Vec3, color: Vec3) -> Vertex {
-        Vertex { pos, color }
+    pub fn new(pos: Vec3, color: Vec3, normal: Vec3, uv: Vec2) -> Vertex {
+        Vertex {
+            pos,
+            color,
+            normal,
+            uv,
+        }
     }
     pub fn size_of() -> usize {
         size_of::<Vertex>()
@@ -24,17 +32,61 @@ impl Vertex {
             .stride(size_of::<Vertex>() as u32)
             .input_rate(VertexInputRate::VERTEX)
     }
+    // Locations follow fields order : they must match the vertex shader inputs
     pub fn attribute_description() -> Vec<VertexInputAttributeDescription> {
-        let pos_description = VertexInputAttributeDescription::default()
-            .binding(0)
-            .format(Format::R32G32_SFLOAT)
-            .location(0)
-            .offset(offset_of!(Vertex, pos) as u32);
-        let color_description = VertexInputAttributeDescription::default()
-            .binding(0)
-            .format(Format::R32G32B32_SFLOAT)
-            .location(1)
-            .offset(offset_of!(Vertex, color) as u32);
-        vec![pos_description, color_description]
+        attribute_descriptions!(Vertex {
+            pos,
+            color,
+            normal,
+            uv
+        })
     }
 }
+
+// Types a vertex field can have, along with the format the shader reads them as
+pub trait AttributeFormat {
+    const FORMAT: Format;
+}
+
+impl AttributeFormat for f32 {
+    const FORMAT: Format = Format::R32_SFLOAT;
+}
+impl AttributeFormat for Vec2 {
+    const FORMAT: Format = Format::R32G32_SFLOAT;
+}
+impl AttributeFormat for Vec3 {
+    const FORMAT: Format = Format::R32G32B32_SFLOAT;
+}
+impl AttributeFormat for Vec4 {
+    const FORMAT: Format = Format::R32G32B32A32_SFLOAT;
+}
+impl AttributeFormat for u32 {
+    const FORMAT: Format = Format::R32_UINT;
+}
+
+// Format of the field returned by "field" (only its type matters)
+pub fn format_of<V, T: AttributeFormat>(_field: fn(&V) -> &T) -> Format {
+    T::FORMAT
+}
+
+// Descriptions (binding 0) of the listed fields, location i for the i-th field,
+// format and offset read from the vertex type so they can't drift from it
+macro_rules! attribute_descriptions {
+    ($vertex:ty { $($field:ident),* $(,)? }) => {
+        [$((
+            format_of(|vertex: &$vertex| &vertex.$field),
+            offset_of!($vertex, $field) as u32,
+        )),*]
+        .into_iter()
+        .enumerate()
+        .map(|(location, (format, offset))| {
+            VertexInputAttributeDescription::default()
+                .binding(0)
+                .location(location as u32)
+                .format(format)
+                .offset(offset)
+        })
+        .collect()
+    };
+}
+use attribute_descriptions;
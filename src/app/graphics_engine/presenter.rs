@@ -1,20 +1,31 @@
 mod swapchain;
 
 use crate::app::graphics_engine::Device;
-use ash::vk::{Fence, Image, PresentInfoKHR, Queue, Semaphore, SurfaceKHR};
+use ash::vk::{
+    self, Extent2D, Fence, Image, PresentInfoKHR, Queue, Semaphore, SurfaceKHR, SwapchainKHR,
+};
 use swapchain::Swapchain;
 
 // Handles presentation :
 // - Hold Swapchain
+// - Tell when it must be recreated (out of date or suboptimal)
 pub struct Presenter {
     swapchain: Swapchain,
     present_queue: Queue,
 }
 
 impl Presenter {
-    pub fn new(device: &Device, surface: &SurfaceKHR) -> Presenter {
-        let swapchain = Swapchain::new(&device, &surface);
-        let present_queue = unsafe { device.get_device_queue(device.infos.present_idx, 0) };
+    pub fn new(device: &Device, surface: &SurfaceKHR, window_extent: Extent2D) -> Presenter {
+        let capabilities = device.query_surface_capabilities(surface);
+        let swapchain = Swapchain::new(
+            device,
+            surface,
+            &capabilities,
+            window_extent,
+            SwapchainKHR::null(),
+        );
+        let present_queue =
+            unsafe { device.get_device_queue(device.infos.present().present_idx, 0) };
         Presenter {
             swapchain,
             present_queue,
@@ -29,20 +40,53 @@ impl Presenter {
         }
     }
 
-    pub fn swapchain_images(&self) -> &Vec<Image> {
+    // Swapchain's images must not be in use anymore
+    pub fn recreate(&mut self, device: &Device, surface: &SurfaceKHR, window_extent: Extent2D) {
+        let capabilities = device.query_surface_capabilities(surface);
+        let swapchain = Swapchain::new(
+            device,
+            surface,
+            &capabilities,
+            window_extent,
+            *self.swapchain,
+        );
+        self.destroy(device);
+        self.swapchain = swapchain;
+    }
+
+    pub fn swapchain_images(&self) -> &[Image] {
         &self.swapchain.images
     }
 
-    pub fn acquire_next_image(&self, device: &Device, image_available: Semaphore) -> (u32, bool) {
-        unsafe {
-            device
-                .swapchain_khr()
-                .acquire_next_image(*self.swapchain, u64::MAX, image_available, Fence::null())
-                .expect("Failed to acquire next swapchain image.")
+    pub fn extent(&self) -> Extent2D {
+        self.swapchain.extent
+    }
+
+    // Whether swapchain images can be read back
+    pub fn is_capturable(&self) -> bool {
+        self.swapchain.capturable
+    }
+
+    // None when the swapchain is out of date (must be recreated)
+    pub fn acquire_next_image(&self, device: &Device, image_available: Semaphore) -> Option<u32> {
+        let result = unsafe {
+            device.swapchain_khr().acquire_next_image(
+                *self.swapchain,
+                u64::MAX,
+                image_available,
+                Fence::null(),
+            )
+        };
+        match result {
+            // suboptimal images are still presentable, recreation happens after present
+            Ok((image_idx, _)) => Some(image_idx),
+            Err(vk::Result::ERROR_OUT_OF_DATE_KHR) => None,
+            Err(error) => panic!("Failed to acquire next swapchain image : {error}"),
         }
     }
 
-    pub fn present(&self, device: &Device, image_idx: u32, rendering_done: Semaphore) {
+    // Returns true when the swapchain should be recreated
+    pub fn present(&self, device: &Device, image_idx: u32, rendering_done: Semaphore) -> bool {
         let swapchains = [*self.swapchain];
         let indices = [image_idx];
         let wait_semaphores = [rendering_done];
@@ -50,11 +94,15 @@ impl Presenter {
             .wait_semaphores(&wait_semaphores)
             .swapchains(&swapchains)
             .image_indices(&indices);
-        unsafe {
+        let result = unsafe {
             device
                 .swapchain_khr()
                 .queue_present(self.present_queue, &present_info)
+        };
+        match result {
+            Ok(suboptimal) => suboptimal,
+            Err(vk::Result::ERROR_OUT_OF_DATE_KHR) => true,
+            Err(error) => panic!("Failed to present image : {error}"),
         }
-        .expect("Failed to present image.");
     }
 }
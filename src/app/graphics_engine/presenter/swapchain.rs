@@ -1,16 +1,19 @@
 use crate::app::graphics_engine::device::Device;
 use ash::vk::{
-    CompositeAlphaFlagsKHR, Image, ImageUsageFlags, SharingMode, SurfaceKHR,
+    CompositeAlphaFlagsKHR, Extent2D, Image, ImageUsageFlags, SurfaceCapabilitiesKHR, SurfaceKHR,
     SwapchainCreateInfoKHR, SwapchainKHR,
 };
 use std::ops::Deref;
 
 // Custom swapchain for presenter.
 // - at least 3 images
-// - hold swapchain images
+// - hold swapchain images and their extent
+// - images can be copied from (screenshots) when the surface allows it
 pub struct Swapchain {
     swapchain: SwapchainKHR,
     pub images: Vec<Image>,
+    pub extent: Extent2D,
+    pub capturable: bool,
 }
 
 // Deref : ash::vk::SwapchainKHR
@@ -22,24 +25,39 @@ impl Deref for Swapchain {
 }
 
 impl Swapchain {
-    pub fn new(device: &Device, surface: &SurfaceKHR) -> Swapchain {
-        let infos = &device.infos;
+    // "old_swapchain" is the one being replaced (or null), it must be destroyed by the caller
+    pub fn new(
+        device: &Device,
+        surface: &SurfaceKHR,
+        capabilities: &SurfaceCapabilitiesKHR,
+        window_extent: Extent2D,
+        old_swapchain: SwapchainKHR,
+    ) -> Swapchain {
+        let infos = device.infos.present();
 
         // SPECIFY : minimum image count (triple buffering if possible)
-        let min_image_count =
-            if infos.capabilities.min_image_count == infos.capabilities.max_image_count {
-                infos.capabilities.min_image_count
-            } else {
-                infos.capabilities.min_image_count + 1
-            };
+        let min_image_count = if capabilities.min_image_count == capabilities.max_image_count {
+            capabilities.min_image_count
+        } else {
+            capabilities.min_image_count + 1
+        };
 
-        // SPECIFY : sharing mode
-        let image_sharing_mode = if infos.graphics_idx != infos.present_idx {
-            SharingMode::CONCURRENT
+        // SPECIFY : extent
+        let extent = choose_extent(capabilities, window_extent);
+
+        // SPECIFY : usage
+        let capturable = capabilities
+            .supported_usage_flags
+            .contains(ImageUsageFlags::TRANSFER_SRC);
+        let image_usage = if capturable {
+            ImageUsageFlags::COLOR_ATTACHMENT | ImageUsageFlags::TRANSFER_SRC
         } else {
-            SharingMode::EXCLUSIVE
+            ImageUsageFlags::COLOR_ATTACHMENT
         };
-        let queue_family_indices = [infos.graphics_idx, infos.present_idx];
+
+        // SPECIFY : sharing mode
+        let (image_sharing_mode, queue_family_indices) =
+            device.bp_sharing(&[device.infos.graphics_idx, infos.present_idx]);
 
         // SPECIFY : behaviour, images
         let create_info = SwapchainCreateInfoKHR::default()
@@ -50,23 +68,41 @@ impl Swapchain {
             // Image : format, extent and usage (= how they will be updated)
             .image_format(infos.surface_format.format)
             .image_color_space(infos.surface_format.color_space)
-            .image_extent(infos.capabilities.current_extent)
-            .image_usage(ImageUsageFlags::COLOR_ATTACHMENT)
+            .image_extent(extent)
+            .image_usage(image_usage)
             // Sharing mode
             .queue_family_indices(&queue_family_indices)
             .image_sharing_mode(image_sharing_mode)
             // Others
             .clipped(true)
             .composite_alpha(CompositeAlphaFlagsKHR::OPAQUE)
-            .pre_transform(infos.capabilities.current_transform)
+            .pre_transform(capabilities.current_transform)
             .image_array_layers(1)
-            .old_swapchain(SwapchainKHR::null());
+            .old_swapchain(old_swapchain);
 
         // CREATE : swapchain and images
         let swapchain = unsafe { device.swapchain_khr().create_swapchain(&create_info, None) }
             .expect("Failed to create swapchain.");
         let images = unsafe { device.swapchain_khr().get_swapchain_images(swapchain) }
             .expect("Failed to extract images.");
-        Swapchain { swapchain, images }
+        Swapchain {
+            swapchain,
+            images,
+            extent,
+            capturable,
+        }
+    }
+}
+
+// Surface's extent, or window's one when the surface lets the swapchain decide (u32::MAX)
+fn choose_extent(capabilities: &SurfaceCapabilitiesKHR, window_extent: Extent2D) -> Extent2D {
+    if capabilities.current_extent.width != u32::MAX {
+        return capabilities.current_extent;
+    }
+    let min = capabilities.min_image_extent;
+    let max = capabilities.max_image_extent;
+    Extent2D {
+        width: window_extent.width.clamp(min.width, max.width),
+        height: window_extent.height.clamp(min.height, max.height),
     }
 }
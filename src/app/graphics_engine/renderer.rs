@@ -1,22 +1,37 @@
 mod commands;
 mod descriptors;
+mod frame;
 mod logic;
+mod mesh_registry;
 mod resources;
 mod shaders;
+mod texture_registry;
+
+use std::{collections::HashSet, path::PathBuf};
 
 use ash::vk::{
-    CommandBuffer, CommandPool, DescriptorPool, DescriptorSet, Extent2D, Fence, Framebuffer, Image,
-    ImageView, PipelineStageFlags, Queue, Semaphore, SubmitInfo,
+    CommandPool, DescriptorPool, Extent2D, Fence, Format, Framebuffer, ImageLayout, ImageView,
+    PipelineStageFlags, Queue, Semaphore, SubmitInfo,
 };
+use commands::DrawCall;
+use frame::Frame;
 use glam::Mat4;
 use logic::{create_framebuffers, Pipeline, RenderPass};
-use resources::MVP;
+use mesh_registry::MeshRegistry;
+use resources::ObjectConstants;
+pub use shaders::ShaderError;
+use shaders::{shader_directory, shader_files, Compiler};
+use texture_registry::TextureRegistry;
 
-use crate::app::{graphics_engine::Device, model::Camera};
+use crate::app::{
+    graphics_engine::Device,
+    model::{Camera, Lighting, MAX_LIGHTS},
+};
 
 use super::{
-    device::{Buffer, MappedBuffer},
-    mesher::{Mesh, Vertex},
+    device::Image,
+    mesher::{Material, Mesh, MeshKey, TextureKey},
+    screenshot::{self, ScreenshotError},
 };
 
 pub struct Renderer {
@@ -24,230 +39,342 @@ pub struct Renderer {
     transfer_queue: Queue,
     graphics_queue: Queue,
     // Resources
+    format: Format,
+    extent: Extent2D,
     swapchain_image_views: Vec<ImageView>,
-    vertices: Buffer,
-    staging_vertices: Buffer,
-    indices: Buffer,
-    staging_indices: Buffer,
-    mvp: MappedBuffer,
+    depth: Image,
+    depth_view: ImageView,
     // Logic
     render_pass: RenderPass,
     framebuffers: Vec<Framebuffer>,
     pipeline: Pipeline,
-    // Descriptors
+    // Kept to rebuild the pipeline when its shaders change
+    shader_compiler: Compiler,
+    // Pools
     uniform_pool: DescriptorPool,
-    mvp_set: DescriptorSet,
-    // Commands
     graphics_pool: CommandPool,
     transfer_pool: CommandPool,
-    draw: CommandBuffer,
-    transfer: CommandBuffer,
-    // Syncs
-    transfer_done: Semaphore,
+    // Meshes and textures (shared by frames in flight)
+    meshes: MeshRegistry,
+    textures: TextureRegistry,
+    // Frames in flight
+    frames: Vec<Frame>,
 }
 
 impl Renderer {
-    pub fn new(device: &Device, swapchain_images: &Vec<Image>) -> Renderer {
+    // Renders to "swapchain_images" (or an offscreen image) of "format",
+    // left in "final_layout" (presenting, reading back)
+    pub fn new(
+        device: &Device,
+        swapchain_images: &[ash::vk::Image],
+        format: Format,
+        final_layout: ImageLayout,
+        extent: Extent2D,
+        frames_in_flight: usize,
+    ) -> Renderer {
         // Queues
         let graphics_queue = unsafe { device.get_device_queue(device.infos.graphics_idx, 0) };
         let transfer_queue = unsafe { device.get_device_queue(device.infos.transfer_idx, 0) };
 
         // Resources
         let swapchain_image_views =
-            resources::create_swapchain_image_views(device, swapchain_images);
-        let vertices = resources::allocate_vertices(device);
-        let staging_vertices = resources::allocate_staging_vertices(device);
-        let indices = resources::allocate_indices(device);
-        let staging_indices = resources::allocate_staging_indices(device);
+            resources::create_swapchain_image_views(device, swapchain_images, format);
+        let depth = resources::allocate_depth(device, extent);
+        let depth_view = resources::create_depth_view(device, &depth);
 
         // Logic
-        let render_pass = RenderPass::new(device);
-        let framebuffers = create_framebuffers(device, &render_pass, &swapchain_image_views);
-        let pipeline = Pipeline::new(device, &render_pass);
-
-        // Descriptors
-        let mvp = resources::allocate_mvp(device);
-        let uniform_pool = descriptors::create_uniform_buffer_pool(device);
-        let set_layouts = [*pipeline.mvp_layout()];
-        let mvp_set =
-            descriptors::allocate_configure_mvp_set(device, &uniform_pool, &set_layouts, &mvp);
-
-        // Commands
-        let graphics_pool = commands::create_graphics_pool(device);
-        let transfer_pool = commands::create_transfer_pool(device);
-        let draw = commands::allocate_draw(device, graphics_pool);
-        let transfer = commands::allocate_record_transfer(
+        let render_pass = RenderPass::new(device, format, final_layout);
+        let framebuffers = create_framebuffers(
             device,
-            transfer_pool,
-            &staging_vertices,
-            &vertices,
-            &staging_indices,
-            &indices,
+            &render_pass,
+            &swapchain_image_views,
+            &depth_view,
+            extent,
         );
+        let mut shader_compiler = Compiler::new(shader_directory());
+        shader_compiler.define("MAX_LIGHTS", &MAX_LIGHTS.to_string());
+        let pipeline = Pipeline::new(device, &render_pass, &shader_compiler)
+            .unwrap_or_else(|err| panic!("Failed to build the pipeline's shaders : {err}"));
+
+        // Pools
+        let uniform_pool = descriptors::create_uniform_buffer_pool(device, frames_in_flight as u32);
+        let graphics_pool = commands::create_graphics_pool(device);
+        let transfer_pool = commands::create_transfer_pool(device);
 
-        // Syncs
-        let transfer_done = device.bp_new_semaphore();
+        // Meshes
+        let meshes = MeshRegistry::new(device, frames_in_flight);
+        let textures = TextureRegistry::new(device, frames_in_flight);
+
+        // Frames in flight
+        let frames = (0..frames_in_flight)
+            .map(|_| {
+                Frame::new(
+                    device,
+                    &uniform_pool,
+                    *pipeline.camera_layout(),
+                    graphics_pool,
+                    transfer_pool,
+                )
+            })
+            .collect();
 
         Renderer {
             graphics_queue,
             transfer_queue,
+            format,
+            extent,
             swapchain_image_views,
-            vertices,
-            staging_vertices,
-            indices,
-            staging_indices,
-            mvp,
-            uniform_pool,
-            mvp_set,
+            depth,
+            depth_view,
             render_pass,
             framebuffers,
             pipeline,
+            shader_compiler,
+            uniform_pool,
             graphics_pool,
             transfer_pool,
-            draw,
-            transfer,
-            transfer_done,
+            meshes,
+            textures,
+            frames,
         }
     }
 
     pub fn destroy(&mut self, device: &Device) {
         unsafe {
-            // Syncs
-            device.destroy_semaphore(self.transfer_done, None);
+            // Frames in flight
+            for frame in &mut self.frames {
+                frame.destroy(device);
+            }
 
-            // Commands
+            // Meshes
+            self.meshes.destroy(device);
+            self.textures.destroy(device);
+
+            // Pools
             device.destroy_command_pool(self.graphics_pool, None);
             device.destroy_command_pool(self.transfer_pool, None);
-
-            // Descriptors
             device.destroy_descriptor_pool(self.uniform_pool, None);
 
-            // Resources
-            for image_view in &mut self.swapchain_image_views {
-                device.destroy_image_view(*image_view, None);
-            }
-            device.ct_destroy_buffer(&mut self.vertices);
-            device.ct_destroy_buffer(&mut self.staging_vertices);
-            device.ct_destroy_buffer(&mut self.indices);
-            device.ct_destroy_buffer(&mut self.staging_indices);
-            device.ct_destroy_mapped_buffer(&mut self.mvp);
+            // Resources and logic that follow the swapchain
+            self.destroy_swapchain_dependents(device);
 
             // Logic
+            self.pipeline.destroy(device);
+            device.destroy_render_pass(*self.render_pass, None);
+        }
+    }
+
+    // Swapchain changed : rebuild image views, depth and framebuffers (they must not be in use)
+    pub fn recreate(
+        &mut self,
+        device: &Device,
+        swapchain_images: &[ash::vk::Image],
+        extent: Extent2D,
+    ) {
+        self.destroy_swapchain_dependents(device);
+        self.extent = extent;
+        self.swapchain_image_views =
+            resources::create_swapchain_image_views(device, swapchain_images, self.format);
+        self.depth = resources::allocate_depth(device, extent);
+        self.depth_view = resources::create_depth_view(device, &self.depth);
+        self.framebuffers = create_framebuffers(
+            device,
+            &self.render_pass,
+            &self.swapchain_image_views,
+            &self.depth_view,
+            extent,
+        );
+    }
+
+    fn destroy_swapchain_dependents(&mut self, device: &Device) {
+        unsafe {
             for framebuffer in &mut self.framebuffers {
                 device.destroy_framebuffer(*framebuffer, None);
             }
-            self.pipeline.destroy(device);
-            device.destroy_render_pass(*self.render_pass, None);
+            for image_view in &mut self.swapchain_image_views {
+                device.destroy_image_view(*image_view, None);
+            }
+            device.destroy_image_view(self.depth_view, None);
+            device.ct_destroy_image(&mut self.depth);
         }
     }
 
+    // What the pipeline's shaders are built from (to watch for changes)
+    pub fn shader_files(&self) -> Vec<PathBuf> {
+        shader_files(self.shader_compiler.directory())
+    }
+
+    // Shaders changed : compile them again and rebuild the pipeline (waits for the device to be
+    // idle). When they fail to compile, the pipeline is kept and the diagnostics returned
+    pub fn reload_shaders(&mut self, device: &Device) -> Result<(), ShaderError> {
+        self.pipeline
+            .rebuild(device, &self.render_pass, &self.shader_compiler)
+    }
+
+    // Whether the geometry of "key" is on the device already (its mesh needn't be built)
+    pub fn has_mesh(&self, key: &MeshKey) -> bool {
+        self.meshes.contains(key)
+    }
+
+    // The geometry of "key" won't be drawn again (its device memory can be reused)
+    pub fn invalidate_mesh(&mut self, key: &MeshKey) {
+        self.meshes.invalidate(key);
+    }
+
+    // The texture of "key" won't be sampled again (its image can be destroyed)
+    pub fn invalidate_texture(&mut self, key: &TextureKey) {
+        self.textures.invalidate(key);
+    }
+
+    // "frame_idx" frame's last rendering must be done
+    // "uploads" : meshes of the drawn keys the renderer doesn't have yet
+    // "image_available", "rendering_done" : None when rendering offscreen (nothing to acquire, present)
+    #[allow(clippy::too_many_arguments)]
     pub fn submit_render(
         &mut self,
         device: &Device,
-        meshes: Vec<(Mat4, Mesh)>,
+        frame_idx: usize,
+        uploads: Vec<(MeshKey, Mesh)>,
+        draws: Vec<(Mat4, MeshKey, Material)>,
         camera: &Camera,
+        lighting: &Lighting,
         swapchain_image_idx: u32,
-        swapchain_extent: Extent2D,
-        image_available: Semaphore,
-        rendering_done: Semaphore,
+        image_available: Option<Semaphore>,
+        rendering_done: Option<Semaphore>,
         fence_rendering_done: Fence,
     ) {
-        // CPU COPY : staging vertices
-        self.copy_vertices(device, &meshes);
+        // RELEASE : what the frame's last rendering used
+        self.frames[frame_idx].release_staging(device);
+        self.meshes.frame_started(device);
+        self.textures.frame_started(device);
 
-        // SUBMIT : transfer
-        let signal_semaphores = [self.transfer_done];
-        self.submit_transfer(device, &signal_semaphores);
+        // UPLOAD : new meshes
+        let mut wait_semaphores: Vec<Semaphore> = image_available.into_iter().collect();
+        let mut wait_dst_stage_mask =
+            vec![PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT; wait_semaphores.len()];
+        if !uploads.is_empty() || self.meshes.wasteful() {
+            let drawn: HashSet<MeshKey> = draws.iter().map(|(_, key, _)| *key).collect();
+            let frame = &mut self.frames[frame_idx];
+            let staging = self.meshes.upload(
+                device,
+                self.transfer_queue,
+                frame.transfer,
+                frame.upload_done,
+                &uploads,
+                &drawn,
+            );
+            frame.keep_staging(staging);
+            wait_semaphores.push(frame.upload_done);
+            wait_dst_stage_mask.push(PipelineStageFlags::VERTEX_INPUT);
+        }
 
-        // CPU COPY : mvp
-        self.copy_mvp(camera, swapchain_extent, &meshes);
+        // STAGE : new textures (copied by the draw commands)
+        let textures = self
+            .textures
+            .missing(draws.iter().map(|(_, _, material)| material));
+        let texture_uploads =
+            self.textures
+                .register(device, self.pipeline.layout.material, &textures);
+
+        // CPU COPY : camera and lighting
+        let extent = self.extent;
+        self.frames[frame_idx].copy_uniforms(camera, lighting, extent);
 
         // RECORD : draw
-        self.record_draw(device, swapchain_image_idx as usize);
+        let frame = &self.frames[frame_idx];
+        let draws: Vec<DrawCall> = draws
+            .iter()
+            .map(|(transform, key, material)| DrawCall {
+                constants: ObjectConstants::new(*transform, material.base_color),
+                range: self.meshes.range(key),
+                material_set: self.textures.set(material),
+            })
+            .collect();
+        self.record_draw(
+            device,
+            frame,
+            swapchain_image_idx as usize,
+            &texture_uploads,
+            &draws,
+        );
 
         // SUBMIT : draw
-        let wait_semaphores = [self.transfer_done, image_available];
-        let wait_dst_stage_mask = [
-            PipelineStageFlags::VERTEX_INPUT,
-            PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT,
-        ];
-        let signal_semaphores = [rendering_done];
+        let signal_semaphores: Vec<Semaphore> = rendering_done.into_iter().collect();
         let signal_fence = fence_rendering_done;
         self.submit_draw(
             device,
+            frame,
             &wait_semaphores,
             &wait_dst_stage_mask,
             &signal_semaphores,
             signal_fence,
         );
+        self.frames[frame_idx]
+            .keep_staging(texture_uploads.into_iter().map(|upload| upload.staging));
     }
 
-    fn copy_vertices(&mut self, device: &Device, meshes: &Vec<(Mat4, Mesh)>) {
-        let vertices = &meshes[0].1.vertices;
-        let indices = &meshes[0].1.indices;
-        unsafe {
-            // map
-            let mapped_vertices = device
-                .allocator()
-                .map_memory(&mut self.staging_vertices.allocation)
-                .expect("Failed to map memory.");
-            let mapped_indices = device
-                .allocator()
-                .map_memory(&mut self.staging_indices.allocation)
-                .expect("Failed to map memory.");
-
-            // copy
-            mapped_vertices.copy_from(
-                vertices.as_ptr() as *const u8,
-                Vertex::size_of() * vertices.len(),
-            );
-            mapped_indices.copy_from(
-                indices.as_ptr() as *const u8,
-                size_of::<u32>() * indices.len(),
-            );
+    // Copy a rendered "image" (in "layout", left so) to the host and wait for it :
+    // tightly packed rows of pixels, in the image's format (unless it can't be converted)
+    // (graphics queue : after the renderings submitted before)
+    pub fn read_back(
+        &self,
+        device: &Device,
+        image: ash::vk::Image,
+        layout: ImageLayout,
+    ) -> Result<Vec<u8>, ScreenshotError> {
+        let pixel_size = screenshot::pixel_size(self.format)?;
+        let mut readback = resources::allocate_readback(device, self.extent, pixel_size);
+        let command_buffer = commands::allocate_transfer(device, self.graphics_pool);
+        commands::record_readback(
+            device,
+            command_buffer,
+            image,
+            layout,
+            self.extent,
+            *readback,
+        );
 
-            // unmap
-            device
-                .allocator()
-                .unmap_memory(&mut self.staging_vertices.allocation);
+        // Submit and wait
+        let fence = device.bp_new_fence(false);
+        let command_buffers = [command_buffer];
+        let submit_info = SubmitInfo::default().command_buffers(&command_buffers);
+        unsafe {
             device
-                .allocator()
-                .unmap_memory(&mut self.staging_indices.allocation);
+                .queue_submit(self.graphics_queue, &[submit_info], fence)
+                .expect("Failed to submit readback.");
         }
-    }
+        device.bp_wait_fence(fence, None);
 
-    fn copy_mvp(&mut self, camera: &Camera, extent: Extent2D, meshes: &Vec<(Mat4, Mesh)>) {
-        let transform = meshes[0].0;
-        let mvp = MVP::from_camera_transform(camera, extent, transform);
-        let ptr: *const MVP = &mvp;
-        unsafe { self.mvp.ptr.copy_from(ptr as *const u8, MVP::size_of()) };
-    }
+        // Copy out
+        let size = pixel_size as usize * self.extent.width as usize * self.extent.height as usize;
+        let pixels = unsafe { std::slice::from_raw_parts(readback.ptr, size) }.to_vec();
 
-    fn submit_transfer(&self, device: &Device, signal_semaphores: &[Semaphore]) {
-        let command_buffers = [self.transfer];
-        let submit_info = SubmitInfo::default()
-            .command_buffers(&command_buffers)
-            .signal_semaphores(signal_semaphores);
         unsafe {
-            device
-                .queue_submit(self.transfer_queue, &[submit_info], Fence::null())
-                .expect("Failed to submit transfer.");
+            device.destroy_fence(fence, None);
+            device.free_command_buffers(self.graphics_pool, &command_buffers);
         }
+        device.ct_destroy_mapped_buffer(&mut readback);
+        Ok(pixels)
+    }
+
+    pub fn extent(&self) -> Extent2D {
+        self.extent
     }
 
     fn submit_draw(
         &self,
         device: &Device,
+        frame: &Frame,
         wait_semaphores: &[Semaphore],
         wait_dst_stage_mask: &[PipelineStageFlags],
         signal_semaphores: &[Semaphore],
         signal_fence: Fence,
     ) {
-        let command_buffers = [self.draw];
+        let command_buffers = [frame.draw];
         let submit_info = SubmitInfo::default()
-            .wait_semaphores(&wait_semaphores)
-            .wait_dst_stage_mask(&wait_dst_stage_mask)
-            .signal_semaphores(&signal_semaphores)
+            .wait_semaphores(wait_semaphores)
+            .wait_dst_stage_mask(wait_dst_stage_mask)
+            .signal_semaphores(signal_semaphores)
             .command_buffers(&command_buffers);
         unsafe {
             device
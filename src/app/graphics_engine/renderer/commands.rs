@@ -1,7 +1,11 @@
 mod draw;
 mod pools;
+mod readback;
+mod texture;
 mod transfer;
 
-pub use draw::allocate_draw;
+pub use draw::{allocate_draw, DrawCall};
 pub use pools::{create_graphics_pool, create_transfer_pool};
-pub use transfer::allocate_record_transfer;
+pub use readback::record_readback;
+pub use texture::TextureUpload;
+pub use transfer::{allocate_transfer, record_copies, BufferCopies};
@@ -1,10 +1,25 @@
 use ash::vk::{
     ClearValue, CommandBuffer, CommandBufferAllocateInfo, CommandBufferBeginInfo,
-    CommandBufferLevel, CommandPool, Framebuffer, IndexType, PipelineBindPoint, Rect2D,
-    RenderPassBeginInfo, SubpassContents,
+    CommandBufferLevel, CommandPool, DescriptorSet, Framebuffer, IndexType, PipelineBindPoint,
+    Rect2D, RenderPassBeginInfo, ShaderStageFlags, SubpassContents, Viewport,
 };
 
-use crate::app::graphics_engine::{mesher::MAX_INDICES, Device, Renderer};
+use crate::app::graphics_engine::{
+    renderer::{
+        frame::Frame,
+        resources::{MeshRange, ObjectConstants},
+    },
+    Device, Renderer,
+};
+
+use super::texture::{record_texture_uploads, TextureUpload};
+
+// One indexed draw : where its mesh is, what is pushed for it and its material's texture
+pub struct DrawCall {
+    pub constants: ObjectConstants,
+    pub range: MeshRange,
+    pub material_set: DescriptorSet,
+}
 
 pub fn allocate_draw(device: &Device, pool: CommandPool) -> CommandBuffer {
     let allocate_info = CommandBufferAllocateInfo::default()
@@ -19,32 +34,57 @@ pub fn allocate_draw(device: &Device, pool: CommandPool) -> CommandBuffer {
 }
 
 impl Renderer {
-    pub fn record_draw(&self, device: &Device, swapchain_image_idx: usize) {
+    // "uploads" : textures to fill before drawing
+    pub fn record_draw(
+        &self,
+        device: &Device,
+        frame: &Frame,
+        swapchain_image_idx: usize,
+        uploads: &[TextureUpload],
+        draws: &[DrawCall],
+    ) {
         unsafe {
             // Begin
             let begin_info = CommandBufferBeginInfo::default();
             device
-                .begin_command_buffer(self.draw, &begin_info)
+                .begin_command_buffer(frame.draw, &begin_info)
                 .expect("Failed to start recording command buffer.");
 
+            // Copy : new textures
+            if !uploads.is_empty() {
+                record_texture_uploads(device, frame.draw, uploads);
+            }
+
             // Begin render pass
-            self.cmd_begin_render_pass(device, swapchain_image_idx);
+            self.cmd_begin_render_pass(device, frame, swapchain_image_idx);
 
             // Bind : pipeline
-            device.cmd_bind_pipeline(self.draw, PipelineBindPoint::GRAPHICS, *self.pipeline);
+            device.cmd_bind_pipeline(frame.draw, PipelineBindPoint::GRAPHICS, *self.pipeline);
+
+            // Set : viewport and scissor covering the whole image
+            let viewport = Viewport::default()
+                .x(0.)
+                .y(0.)
+                .width(self.extent.width as f32)
+                .height(self.extent.height as f32)
+                .min_depth(0.)
+                .max_depth(1.);
+            device.cmd_set_viewport(frame.draw, 0, &[viewport]);
+            let scissor = Rect2D::default().extent(self.extent);
+            device.cmd_set_scissor(frame.draw, 0, &[scissor]);
 
             // Bind : vertices
-            let buffers = [*self.vertices];
+            let buffers = [*self.meshes.vertices];
             let offsets = [0];
-            device.cmd_bind_vertex_buffers(self.draw, 0, &buffers, &offsets);
+            device.cmd_bind_vertex_buffers(frame.draw, 0, &buffers, &offsets);
 
             // Bind : indices
-            device.cmd_bind_index_buffer(self.draw, *self.indices, 0, IndexType::UINT32);
+            device.cmd_bind_index_buffer(frame.draw, *self.meshes.indices, 0, IndexType::UINT32);
 
-            // Bind : MVP
-            let sets = [self.mvp_set];
+            // Bind : camera
+            let sets = [frame.camera_set];
             device.cmd_bind_descriptor_sets(
-                self.draw,
+                frame.draw,
                 PipelineBindPoint::GRAPHICS,
                 self.pipeline.layout.pipeline,
                 0,
@@ -52,23 +92,47 @@ impl Renderer {
                 &[],
             );
 
-            // Draw
-            device.cmd_draw_indexed(self.draw, MAX_INDICES as u32, 1, 0, 0, 0);
+            // Draw : one indexed draw per mesh, its constants pushed and its texture bound beforehand
+            for draw in draws {
+                device.cmd_bind_descriptor_sets(
+                    frame.draw,
+                    PipelineBindPoint::GRAPHICS,
+                    self.pipeline.layout.pipeline,
+                    1,
+                    &[draw.material_set],
+                    &[],
+                );
+                device.cmd_push_constants(
+                    frame.draw,
+                    self.pipeline.layout.pipeline,
+                    ShaderStageFlags::VERTEX | ShaderStageFlags::FRAGMENT,
+                    0,
+                    draw.constants.as_bytes(),
+                );
+                device.cmd_draw_indexed(
+                    frame.draw,
+                    draw.range.index_count,
+                    1,
+                    draw.range.first_index,
+                    draw.range.vertex_offset,
+                    0,
+                );
+            }
 
             // End render pass
-            device.cmd_end_render_pass(self.draw);
+            device.cmd_end_render_pass(frame.draw);
 
             // End
             device
-                .end_command_buffer(self.draw)
+                .end_command_buffer(frame.draw)
                 .expect("Failed to record command buffer.");
         }
     }
 
-    fn cmd_begin_render_pass(&self, device: &Device, swapchain_image_idx: usize) {
+    fn cmd_begin_render_pass(&self, device: &Device, frame: &Frame, swapchain_image_idx: usize) {
         // Params
         let framebuffer: &Framebuffer = &self.framebuffers[swapchain_image_idx];
-        let render_area = Rect2D::default().extent(device.infos.capabilities.current_extent);
+        let render_area = Rect2D::default().extent(self.extent);
         let clear_values = clear_values();
 
         // Cmd
@@ -78,14 +142,16 @@ impl Renderer {
             .render_area(render_area)
             .clear_values(&clear_values);
         unsafe {
-            device.cmd_begin_render_pass(self.draw, &render_pass_begin, SubpassContents::INLINE)
+            device.cmd_begin_render_pass(frame.draw, &render_pass_begin, SubpassContents::INLINE)
         };
     }
 }
 
-// Clears to black
+// Clears to black and farthest depth (depth is reversed : 0)
 fn clear_values() -> Vec<ClearValue> {
     let mut clear_color = ClearValue::default();
     clear_color.color.float32 = [0., 0., 0., 1.];
-    vec![clear_color]
+    let mut clear_depth = ClearValue::default();
+    clear_depth.depth_stencil.depth = 0.;
+    vec![clear_color, clear_depth]
 }
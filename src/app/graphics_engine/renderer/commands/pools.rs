@@ -24,6 +24,6 @@ pub fn create_transfer_pool(device: &Device) -> CommandPool {
     create_pool(
         device,
         device.infos.transfer_idx,
-        CommandPoolCreateFlags::empty(),
+        CommandPoolCreateFlags::RESET_COMMAND_BUFFER,
     )
 }
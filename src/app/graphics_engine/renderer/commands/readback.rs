@@ -0,0 +1,111 @@
+use ash::vk::{
+    AccessFlags, Buffer, BufferImageCopy, BufferMemoryBarrier, CommandBuffer,
+    CommandBufferBeginInfo, CommandBufferUsageFlags, DependencyFlags, Extent2D, Extent3D, Image,
+    ImageAspectFlags, ImageLayout, ImageMemoryBarrier, ImageSubresourceLayers,
+    ImageSubresourceRange, PipelineStageFlags, QUEUE_FAMILY_IGNORED, WHOLE_SIZE,
+};
+
+use crate::app::graphics_engine::Device;
+
+// Record the copy of a rendered color "image" (in "layout") to "buffer", tightly packed rows.
+// The image goes back to "layout" afterwards, the buffer is then readable by the host
+pub fn record_readback(
+    device: &Device,
+    command_buffer: CommandBuffer,
+    image: Image,
+    layout: ImageLayout,
+    extent: Extent2D,
+    buffer: Buffer,
+) {
+    // Begin
+    let begin_info =
+        CommandBufferBeginInfo::default().flags(CommandBufferUsageFlags::ONE_TIME_SUBMIT);
+    unsafe {
+        device
+            .begin_command_buffer(command_buffer, &begin_info)
+            .expect("Failed to begin readback.");
+    }
+
+    // Barrier : rendering done, image ready to be copied
+    let subresource_range = ImageSubresourceRange::default()
+        .aspect_mask(ImageAspectFlags::COLOR)
+        .level_count(1)
+        .layer_count(1);
+    let to_transfer = ImageMemoryBarrier::default()
+        .src_access_mask(AccessFlags::COLOR_ATTACHMENT_WRITE)
+        .dst_access_mask(AccessFlags::TRANSFER_READ)
+        .old_layout(layout)
+        .new_layout(ImageLayout::TRANSFER_SRC_OPTIMAL)
+        .src_queue_family_index(QUEUE_FAMILY_IGNORED)
+        .dst_queue_family_index(QUEUE_FAMILY_IGNORED)
+        .image(image)
+        .subresource_range(subresource_range);
+    unsafe {
+        device.cmd_pipeline_barrier(
+            command_buffer,
+            PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT,
+            PipelineStageFlags::TRANSFER,
+            DependencyFlags::empty(),
+            &[],
+            &[],
+            &[to_transfer],
+        );
+    }
+
+    // Copy
+    let region = BufferImageCopy::default()
+        .buffer_offset(0)
+        .buffer_row_length(0) // tightly packed
+        .buffer_image_height(0)
+        .image_subresource(
+            ImageSubresourceLayers::default()
+                .aspect_mask(ImageAspectFlags::COLOR)
+                .layer_count(1),
+        )
+        .image_extent(Extent3D::from(extent).depth(1));
+    unsafe {
+        device.cmd_copy_image_to_buffer(
+            command_buffer,
+            image,
+            ImageLayout::TRANSFER_SRC_OPTIMAL,
+            buffer,
+            &[region],
+        );
+    }
+
+    // Barriers : image back to its layout, buffer visible to the host
+    let back = ImageMemoryBarrier::default()
+        .src_access_mask(AccessFlags::TRANSFER_READ)
+        .dst_access_mask(AccessFlags::empty())
+        .old_layout(ImageLayout::TRANSFER_SRC_OPTIMAL)
+        .new_layout(layout)
+        .src_queue_family_index(QUEUE_FAMILY_IGNORED)
+        .dst_queue_family_index(QUEUE_FAMILY_IGNORED)
+        .image(image)
+        .subresource_range(subresource_range);
+    let to_host = BufferMemoryBarrier::default()
+        .src_access_mask(AccessFlags::TRANSFER_WRITE)
+        .dst_access_mask(AccessFlags::HOST_READ)
+        .src_queue_family_index(QUEUE_FAMILY_IGNORED)
+        .dst_queue_family_index(QUEUE_FAMILY_IGNORED)
+        .buffer(buffer)
+        .size(WHOLE_SIZE);
+    unsafe {
+        device.cmd_pipeline_barrier(
+            command_buffer,
+            PipelineStageFlags::TRANSFER,
+            PipelineStageFlags::HOST | PipelineStageFlags::BOTTOM_OF_PIPE,
+            DependencyFlags::empty(),
+            &[],
+            &[to_host],
+            &[back],
+        );
+    }
+
+    // End
+    unsafe {
+        device
+            .end_command_buffer(command_buffer)
+            .expect("Failed to record readback.");
+    }
+}
@@ -0,0 +1,117 @@
+use ash::vk::{
+    AccessFlags, BufferImageCopy, CommandBuffer, DependencyFlags, Extent3D, ImageAspectFlags,
+    ImageLayout, ImageMemoryBarrier, ImageSubresourceLayers, ImageSubresourceRange,
+    PipelineStageFlags, QUEUE_FAMILY_IGNORED,
+};
+
+use crate::app::graphics_engine::{device::MappedBuffer, Device};
+
+// A texture image to fill from its staging buffer (tightly packed rows)
+pub struct TextureUpload {
+    pub staging: MappedBuffer,
+    pub image: ash::vk::Image,
+    pub width: u32,
+    pub height: u32,
+}
+
+// Record the copies of "uploads", leaving their images ready to be sampled by fragment shaders
+// (outside of a render pass)
+pub fn record_texture_uploads(
+    device: &Device,
+    command_buffer: CommandBuffer,
+    uploads: &[TextureUpload],
+) {
+    let subresource_range = ImageSubresourceRange::default()
+        .aspect_mask(ImageAspectFlags::COLOR)
+        .level_count(1)
+        .layer_count(1);
+    let barrier = |image, old_layout, new_layout, src_access_mask, dst_access_mask| {
+        ImageMemoryBarrier::default()
+            .src_access_mask(src_access_mask)
+            .dst_access_mask(dst_access_mask)
+            .old_layout(old_layout)
+            .new_layout(new_layout)
+            .src_queue_family_index(QUEUE_FAMILY_IGNORED)
+            .dst_queue_family_index(QUEUE_FAMILY_IGNORED)
+            .image(image)
+            .subresource_range(subresource_range)
+    };
+
+    // Barriers : images ready to be copied to (their content is discarded)
+    let to_transfer: Vec<ImageMemoryBarrier> = uploads
+        .iter()
+        .map(|upload| {
+            barrier(
+                upload.image,
+                ImageLayout::UNDEFINED,
+                ImageLayout::TRANSFER_DST_OPTIMAL,
+                AccessFlags::empty(),
+                AccessFlags::TRANSFER_WRITE,
+            )
+        })
+        .collect();
+    unsafe {
+        device.cmd_pipeline_barrier(
+            command_buffer,
+            PipelineStageFlags::TOP_OF_PIPE,
+            PipelineStageFlags::TRANSFER,
+            DependencyFlags::empty(),
+            &[],
+            &[],
+            &to_transfer,
+        );
+    }
+
+    // Copies
+    for upload in uploads {
+        let region = BufferImageCopy::default()
+            .buffer_offset(0)
+            .buffer_row_length(0) // tightly packed
+            .buffer_image_height(0)
+            .image_subresource(
+                ImageSubresourceLayers::default()
+                    .aspect_mask(ImageAspectFlags::COLOR)
+                    .layer_count(1),
+            )
+            .image_extent(
+                Extent3D::default()
+                    .width(upload.width)
+                    .height(upload.height)
+                    .depth(1),
+            );
+        unsafe {
+            device.cmd_copy_buffer_to_image(
+                command_buffer,
+                *upload.staging,
+                upload.image,
+                ImageLayout::TRANSFER_DST_OPTIMAL,
+                &[region],
+            );
+        }
+    }
+
+    // Barriers : images ready to be sampled
+    let to_shader: Vec<ImageMemoryBarrier> = uploads
+        .iter()
+        .map(|upload| {
+            barrier(
+                upload.image,
+                ImageLayout::TRANSFER_DST_OPTIMAL,
+                ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+                AccessFlags::TRANSFER_WRITE,
+                AccessFlags::SHADER_READ,
+            )
+        })
+        .collect();
+    unsafe {
+        device.cmd_pipeline_barrier(
+            command_buffer,
+            PipelineStageFlags::TRANSFER,
+            PipelineStageFlags::FRAGMENT_SHADER,
+            DependencyFlags::empty(),
+            &[],
+            &[],
+            &to_shader,
+        );
+    }
+}
@@ -1,34 +1,12 @@
 use ash::vk::{
-    Buffer, BufferCopy, CommandBuffer, CommandBufferAllocateInfo, CommandBufferBeginInfo,
-    CommandBufferLevel, CommandPool,
+    AccessFlags, Buffer, BufferCopy, CommandBuffer, CommandBufferAllocateInfo,
+    CommandBufferBeginInfo, CommandBufferLevel, CommandBufferUsageFlags, CommandPool,
+    DependencyFlags, MemoryBarrier, PipelineStageFlags,
 };
 
-use crate::app::graphics_engine::{
-    mesher::{Vertex, MAX_INDICES, MAX_VERTICES},
-    Device,
-};
-
-pub fn allocate_record_transfer(
-    device: &Device,
-    pool: CommandPool,
-    staging_vertices: &Buffer,
-    vertices: &Buffer,
-    staging_indices: &Buffer,
-    indices: &Buffer,
-) -> CommandBuffer {
-    let transfer = allocate_transfer(device, pool);
-    record_transfer(
-        device,
-        &transfer,
-        staging_vertices,
-        vertices,
-        staging_indices,
-        indices,
-    );
-    transfer
-}
+use crate::app::graphics_engine::Device;
 
-fn allocate_transfer(device: &Device, pool: CommandPool) -> CommandBuffer {
+pub fn allocate_transfer(device: &Device, pool: CommandPool) -> CommandBuffer {
     let allocate_info = CommandBufferAllocateInfo::default()
         .command_pool(pool)
         .level(CommandBufferLevel::PRIMARY)
@@ -40,38 +18,61 @@ fn allocate_transfer(device: &Device, pool: CommandPool) -> CommandBuffer {
     }
 }
 
-fn record_transfer(
+// Regions to copy from a buffer (first) to another (second)
+pub type BufferCopies = (Buffer, Buffer, Vec<BufferCopy>);
+
+// Record "copies" for a single submit, empty regions are skipped.
+// "reads_earlier_writes" : the copies read buffers written by transfers submitted before
+// on the queue (which must be done writing them first)
+pub fn record_copies(
     device: &Device,
-    transfer: &CommandBuffer,
-    staging_vertices: &Buffer,
-    vertices: &Buffer,
-    staging_indices: &Buffer,
-    indices: &Buffer,
+    command_buffer: CommandBuffer,
+    copies: &[BufferCopies],
+    reads_earlier_writes: bool,
 ) {
     // Begin
-    let begin_info = CommandBufferBeginInfo::default();
+    let begin_info =
+        CommandBufferBeginInfo::default().flags(CommandBufferUsageFlags::ONE_TIME_SUBMIT);
     unsafe {
         device
-            .begin_command_buffer(*transfer, &begin_info)
+            .begin_command_buffer(command_buffer, &begin_info)
             .expect("Failed to begin transfer.");
     }
 
-    // Copy vertices
-    let region = BufferCopy::default() // Offset of 0 for src and dst
-        .size(Vertex::size_of() as u64 * MAX_VERTICES);
-    let regions = [region];
-    unsafe { device.cmd_copy_buffer(*transfer, *staging_vertices, *vertices, &regions) };
+    // Sync : earlier writes before these reads
+    if reads_earlier_writes {
+        let written = MemoryBarrier::default()
+            .src_access_mask(AccessFlags::TRANSFER_WRITE)
+            .dst_access_mask(AccessFlags::TRANSFER_READ);
+        unsafe {
+            device.cmd_pipeline_barrier(
+                command_buffer,
+                PipelineStageFlags::TRANSFER,
+                PipelineStageFlags::TRANSFER,
+                DependencyFlags::empty(),
+                &[written],
+                &[],
+                &[],
+            );
+        }
+    }
 
-    // Copy indices
-    let region = BufferCopy::default() // Offset of 0 for src and dst
-        .size(size_of::<u32>() as u64 * MAX_INDICES);
-    let regions = [region];
-    unsafe { device.cmd_copy_buffer(*transfer, *staging_indices, *indices, &regions) };
+    // Copy
+    for (src, dst, regions) in copies {
+        let regions: Vec<BufferCopy> = regions
+            .iter()
+            .filter(|region| region.size > 0)
+            .copied()
+            .collect();
+        if !regions.is_empty() {
+            unsafe { device.cmd_copy_buffer(command_buffer, *src, *dst, &regions) };
+        }
+    }
 
     // End
     unsafe {
         device
-            .end_command_buffer(*transfer)
+            .end_command_buffer(command_buffer)
             .expect("Failed to record transfer.");
     }
 }
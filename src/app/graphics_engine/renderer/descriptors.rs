@@ -1,5 +1,7 @@
-mod mvp;
+mod camera;
 mod pools;
+mod texture;
 
-pub use mvp::allocate_configure_mvp_set;
-pub use pools::create_uniform_buffer_pool;
+pub use camera::allocate_configure_camera_set;
+pub use pools::{create_texture_pool, create_uniform_buffer_pool};
+pub use texture::{allocate_configure_texture_set, configure_texture_set};
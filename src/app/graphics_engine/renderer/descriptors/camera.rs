@@ -5,7 +5,7 @@ use ash::vk::{
 
 use crate::app::graphics_engine::Device;
 
-pub fn allocate_configure_mvp_set(
+pub fn allocate_configure_camera_set(
     device: &Device,
     descriptor_pool: &DescriptorPool,
     set_layouts: &[DescriptorSetLayout],
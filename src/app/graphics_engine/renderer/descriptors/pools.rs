@@ -27,12 +27,24 @@ fn create_descriptor_pool(
     }
 }
 
-pub fn create_uniform_buffer_pool(device: &Device) -> DescriptorPool {
+// "count" sets of 1 uniform buffer
+pub fn create_uniform_buffer_pool(device: &Device, count: u32) -> DescriptorPool {
     create_descriptor_pool(
         device,
-        1,
+        count,
         DescriptorType::UNIFORM_BUFFER,
         DescriptorPoolCreateFlags::empty(),
-        1,
+        count,
+    )
+}
+
+// "count" sets of 1 combined image sampler
+pub fn create_texture_pool(device: &Device, count: u32) -> DescriptorPool {
+    create_descriptor_pool(
+        device,
+        count,
+        DescriptorType::COMBINED_IMAGE_SAMPLER,
+        DescriptorPoolCreateFlags::empty(),
+        count,
     )
 }
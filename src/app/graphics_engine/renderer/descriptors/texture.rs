@@ -0,0 +1,42 @@
+use ash::vk::{
+    DescriptorImageInfo, DescriptorPool, DescriptorSet, DescriptorSetLayout, DescriptorType,
+    ImageLayout, ImageView, Sampler, WriteDescriptorSet,
+};
+
+use crate::app::graphics_engine::Device;
+
+pub fn allocate_configure_texture_set(
+    device: &Device,
+    descriptor_pool: &DescriptorPool,
+    set_layouts: &[DescriptorSetLayout],
+    view: ImageView,
+    sampler: Sampler,
+) -> DescriptorSet {
+    let set = device.bp_allocate_descriptor_sets(descriptor_pool, set_layouts)[0];
+    configure_texture_set(device, set, view, sampler);
+    set
+}
+
+// Binds "view" to "set" (allocated before, no draw pending may use it)
+pub fn configure_texture_set(
+    device: &Device,
+    set: DescriptorSet,
+    view: ImageView,
+    sampler: Sampler,
+) {
+    let image_info = DescriptorImageInfo::default()
+        .image_view(view)
+        .sampler(sampler)
+        .image_layout(ImageLayout::SHADER_READ_ONLY_OPTIMAL);
+    let image_infos = [image_info];
+    let write = WriteDescriptorSet::default()
+        .image_info(&image_infos)
+        .dst_set(set)
+        .dst_binding(0)
+        .dst_array_element(0)
+        .descriptor_type(DescriptorType::COMBINED_IMAGE_SAMPLER)
+        .descriptor_count(1);
+    let descriptor_writes = [write];
+
+    unsafe { device.update_descriptor_sets(&descriptor_writes, &[]) };
+}
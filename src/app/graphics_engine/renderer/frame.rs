@@ -0,0 +1,94 @@
+use ash::vk::{
+    CommandBuffer, CommandPool, DescriptorPool, DescriptorSet, DescriptorSetLayout, Extent2D,
+    Semaphore,
+};
+
+use crate::app::{
+    graphics_engine::{device::MappedBuffer, Device},
+    model::{Camera, Lighting},
+};
+
+use super::{
+    commands, descriptors,
+    resources::{self, FrameUniforms},
+};
+
+// Everything a frame in flight writes to, so that frames never wait on each other's resources
+// (meshes are shared by all frames : they live in the renderer's MeshRegistry)
+pub struct Frame {
+    // Resources
+    uniforms: MappedBuffer,
+    // Staging buffers of the uploads recorded in "transfer" and "draw" (destroyed once done)
+    staging: Vec<MappedBuffer>,
+    // Descriptors
+    pub camera_set: DescriptorSet,
+    // Commands
+    pub draw: CommandBuffer,
+    pub transfer: CommandBuffer,
+    // Syncs
+    pub upload_done: Semaphore,
+}
+
+impl Frame {
+    pub fn new(
+        device: &Device,
+        uniform_pool: &DescriptorPool,
+        camera_layout: DescriptorSetLayout,
+        graphics_pool: CommandPool,
+        transfer_pool: CommandPool,
+    ) -> Frame {
+        // Resources
+        let uniforms = resources::allocate_uniforms(device);
+
+        // Descriptors
+        let set_layouts = [camera_layout];
+        let camera_set = descriptors::allocate_configure_camera_set(
+            device,
+            uniform_pool,
+            &set_layouts,
+            &uniforms,
+        );
+
+        // Commands
+        let draw = commands::allocate_draw(device, graphics_pool);
+        let transfer = commands::allocate_transfer(device, transfer_pool);
+
+        Frame {
+            uniforms,
+            staging: Vec::new(),
+            camera_set,
+            draw,
+            transfer,
+            upload_done: device.bp_new_semaphore(),
+        }
+    }
+
+    // Command buffers and descriptor sets are freed with their pools
+    pub fn destroy(&mut self, device: &Device) {
+        unsafe { device.destroy_semaphore(self.upload_done, None) };
+        self.release_staging(device);
+        device.ct_destroy_mapped_buffer(&mut self.uniforms);
+    }
+
+    // Kept until the frame's rendering is done
+    pub fn keep_staging(&mut self, staging: impl IntoIterator<Item = MappedBuffer>) {
+        self.staging.extend(staging);
+    }
+
+    // The frame's last rendering must be done
+    pub fn release_staging(&mut self, device: &Device) {
+        for mut buffer in self.staging.drain(..) {
+            device.ct_destroy_mapped_buffer(&mut buffer);
+        }
+    }
+
+    pub fn copy_uniforms(&mut self, camera: &Camera, lighting: &Lighting, extent: Extent2D) {
+        let uniforms = FrameUniforms::new(camera, lighting, extent);
+        let ptr: *const FrameUniforms = &uniforms;
+        unsafe {
+            self.uniforms
+                .ptr
+                .copy_from(ptr as *const u8, FrameUniforms::size_of())
+        };
+    }
+}
@@ -5,13 +5,20 @@ use crate::app::graphics_engine::Device;
 pub fn create_framebuffers(
     device: &Device,
     render_pass: &RenderPass,
-    swapchain_image_views: &Vec<ImageView>,
+    swapchain_image_views: &[ImageView],
+    depth_view: &ImageView,
+    extent: Extent2D,
 ) -> Vec<Framebuffer> {
-    let extent = device.infos.capabilities.current_extent;
     swapchain_image_views
         .iter()
         .map(|swapchain_image_view| {
-            create_framebuffer(device, render_pass, swapchain_image_view, extent)
+            create_framebuffer(
+                device,
+                render_pass,
+                swapchain_image_view,
+                depth_view,
+                extent,
+            )
         })
         .collect()
 }
@@ -20,9 +27,10 @@ fn create_framebuffer(
     device: &Device,
     render_pass: &RenderPass,
     image_view: &ImageView,
+    depth_view: &ImageView,
     extent: Extent2D,
 ) -> ash::vk::Framebuffer {
-    let attachments = [*image_view];
+    let attachments = [*image_view, *depth_view];
 
     let create_info = FramebufferCreateInfo::default()
         .render_pass(*render_pass)
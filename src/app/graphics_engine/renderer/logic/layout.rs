@@ -1,23 +1,35 @@
 use ash::vk::{
     DescriptorSetLayout, DescriptorSetLayoutBinding, DescriptorSetLayoutCreateInfo, DescriptorType,
-    PipelineLayout, PipelineLayoutCreateInfo, ShaderStageFlags,
+    PipelineLayout, PipelineLayoutCreateInfo, PushConstantRange, ShaderStageFlags,
 };
 
-use crate::app::graphics_engine::Device;
+use crate::app::graphics_engine::{renderer::resources::ObjectConstants, Device};
 
 pub struct Layout {
     pub pipeline: PipelineLayout,
-    pub mvp: DescriptorSetLayout,
+    // Set 0 : frame uniforms, set 1 : the drawn material's texture
+    pub camera: DescriptorSetLayout,
+    pub material: DescriptorSetLayout,
 }
 
 impl Layout {
     pub fn new(device: &Device) -> Layout {
         // Sets
-        let mvp_layout = create_mvp_layout(device);
-        let set_layouts = [mvp_layout];
+        let camera_layout = create_camera_layout(device);
+        let material_layout = create_material_layout(device);
+        let set_layouts = [camera_layout, material_layout];
+
+        // Push constants : model matrix and base color of the object being drawn
+        let object_range = PushConstantRange::default()
+            .stage_flags(ShaderStageFlags::VERTEX | ShaderStageFlags::FRAGMENT)
+            .offset(0)
+            .size(ObjectConstants::size_of() as u32);
+        let push_constant_ranges = [object_range];
 
         // Creation
-        let create_info = PipelineLayoutCreateInfo::default().set_layouts(&set_layouts);
+        let create_info = PipelineLayoutCreateInfo::default()
+            .set_layouts(&set_layouts)
+            .push_constant_ranges(&push_constant_ranges);
         let pipeline_layout = unsafe {
             device
                 .create_pipeline_layout(&create_info, None)
@@ -25,25 +37,46 @@ impl Layout {
         };
         Layout {
             pipeline: pipeline_layout,
-            mvp: mvp_layout,
+            camera: camera_layout,
+            material: material_layout,
         }
     }
 
     pub fn destroy(&mut self, device: &Device) {
         unsafe {
-            device.destroy_descriptor_set_layout(self.mvp, None);
+            device.destroy_descriptor_set_layout(self.camera, None);
+            device.destroy_descriptor_set_layout(self.material, None);
             device.destroy_pipeline_layout(self.pipeline, None);
         }
     }
 }
 
-fn create_mvp_layout(device: &Device) -> DescriptorSetLayout {
+fn create_camera_layout(device: &Device) -> DescriptorSetLayout {
     // Bindings
     let binding = DescriptorSetLayoutBinding::default()
         .binding(0)
         .descriptor_count(1)
         .descriptor_type(DescriptorType::UNIFORM_BUFFER)
-        .stage_flags(ShaderStageFlags::VERTEX);
+        // Camera for the vertices, lighting for the fragments
+        .stage_flags(ShaderStageFlags::VERTEX | ShaderStageFlags::FRAGMENT);
+    let bindings = [binding];
+
+    // Creation
+    let create_info = DescriptorSetLayoutCreateInfo::default().bindings(&bindings);
+    unsafe {
+        device
+            .create_descriptor_set_layout(&create_info, None)
+            .expect("Failed to create descriptor set")
+    }
+}
+
+fn create_material_layout(device: &Device) -> DescriptorSetLayout {
+    // Bindings
+    let binding = DescriptorSetLayoutBinding::default()
+        .binding(0)
+        .descriptor_count(1)
+        .descriptor_type(DescriptorType::COMBINED_IMAGE_SAMPLER)
+        .stage_flags(ShaderStageFlags::FRAGMENT);
     let bindings = [binding];
 
     // Creation
@@ -1,15 +1,22 @@
 use std::ops::Deref;
 
-use crate::app::graphics_engine::{mesher::Vertex, renderer::shaders::Compiler, Device};
+use crate::app::graphics_engine::{
+    mesher::Vertex,
+    renderer::shaders::{Compiler, ShaderError},
+    Device,
+};
 
 use ash::vk::{
-    ColorComponentFlags, CullModeFlags, DescriptorSetLayout, FrontFace, GraphicsPipelineCreateInfo,
-    Offset2D, PipelineCache, PipelineColorBlendAttachmentState, PipelineColorBlendStateCreateInfo,
-    PipelineInputAssemblyStateCreateInfo, PipelineMultisampleStateCreateInfo,
-    PipelineRasterizationStateCreateInfo, PipelineShaderStageCreateInfo,
-    PipelineVertexInputStateCreateInfo, PipelineViewportStateCreateInfo, PolygonMode,
-    PrimitiveTopology, Rect2D, SampleCountFlags, ShaderStageFlags, Viewport,
+    ColorComponentFlags, CompareOp, CullModeFlags, DescriptorSetLayout, DynamicState, FrontFace,
+    GraphicsPipelineCreateInfo, PipelineCache, PipelineColorBlendAttachmentState,
+    PipelineColorBlendStateCreateInfo, PipelineDepthStencilStateCreateInfo,
+    PipelineDynamicStateCreateInfo, PipelineInputAssemblyStateCreateInfo,
+    PipelineMultisampleStateCreateInfo, PipelineRasterizationStateCreateInfo,
+    PipelineShaderStageCreateInfo, PipelineVertexInputStateCreateInfo,
+    PipelineViewportStateCreateInfo, PolygonMode, PrimitiveTopology, SampleCountFlags,
+    ShaderStageFlags,
 };
+use shaderc::ShaderKind;
 
 use super::layout::Layout;
 
@@ -27,109 +34,42 @@ impl Deref for Pipeline {
 }
 
 impl Pipeline {
-    pub fn new(device: &Device, render_pass: &ash::vk::RenderPass) -> Pipeline {
-        let extent = &device.infos.capabilities.current_extent;
-
-        // compiling shaders
-        let shader_compiler = Compiler::new();
-        let vertex = shader_compiler.vertex(device);
-        let fragment = shader_compiler.fragment(device);
-
-        // SPECIFY : programmable stages
-        let vertex_stage_info = PipelineShaderStageCreateInfo::default()
-            .module(vertex)
-            .stage(ShaderStageFlags::VERTEX)
-            .name(c"main");
-
-        let fragment_stage_info = PipelineShaderStageCreateInfo::default()
-            .module(fragment)
-            .stage(ShaderStageFlags::FRAGMENT)
-            .name(c"main");
-
-        let shader_stages = [vertex_stage_info, fragment_stage_info];
-
-        // SPECIFY : fixed funtions stages
-        let vertex_binding_descriptions = [Vertex::binding_description()];
-        let vertex_attribute_descriptions = Vertex::attribute_description();
-        let vertex_input_state = PipelineVertexInputStateCreateInfo::default()
-            .vertex_binding_descriptions(&vertex_binding_descriptions)
-            .vertex_attribute_descriptions(&vertex_attribute_descriptions);
-
-        let input_assembly_state = PipelineInputAssemblyStateCreateInfo::default()
-            .topology(PrimitiveTopology::TRIANGLE_LIST)
-            .primitive_restart_enable(false);
-
-        let viewport = Viewport::default()
-            .x(0.)
-            .y(0.)
-            .height(extent.height as f32)
-            .width(extent.width as f32)
-            .min_depth(0.)
-            .max_depth(1.);
-        let viewports = [viewport];
-
-        let scissor = Rect2D::default()
-            .offset(Offset2D::default().x(0).y(0))
-            .extent(*extent);
-        let scissors = [scissor];
-
-        let viewport_state = PipelineViewportStateCreateInfo::default()
-            .viewports(&viewports)
-            .scissors(&scissors);
-
-        let rasterization_state = PipelineRasterizationStateCreateInfo::default()
-            .depth_clamp_enable(false)
-            .rasterizer_discard_enable(false)
-            .polygon_mode(PolygonMode::FILL)
-            .line_width(1.)
-            .cull_mode(CullModeFlags::BACK)
-            .front_face(FrontFace::CLOCKWISE)
-            .depth_bias_enable(false);
-
-        let multisample_state = PipelineMultisampleStateCreateInfo::default()
-            .sample_shading_enable(false)
-            .rasterization_samples(SampleCountFlags::TYPE_1);
-
-        let color_blend_attachment = PipelineColorBlendAttachmentState::default()
-            .color_write_mask(ColorComponentFlags::RGBA)
-            .blend_enable(false);
-        let attachments = [color_blend_attachment];
-        let color_blend_state = PipelineColorBlendStateCreateInfo::default()
-            .logic_op_enable(false)
-            .attachments(&attachments);
-
-        // Layout
-        let layout = Layout::new(device);
-
-        // CREATE : pipeline
-        let pipeline_info = GraphicsPipelineCreateInfo::default()
-            .stages(&shader_stages)
-            .vertex_input_state(&vertex_input_state)
-            .input_assembly_state(&input_assembly_state)
-            .viewport_state(&viewport_state)
-            .rasterization_state(&rasterization_state)
-            .multisample_state(&multisample_state)
-            .color_blend_state(&color_blend_state)
-            .layout(layout.pipeline)
-            .render_pass(*render_pass)
-            .subpass(0);
-
-        let create_info = [pipeline_info];
-
-        let pipeline = unsafe {
+    pub fn new(
+        device: &Device,
+        render_pass: &ash::vk::RenderPass,
+        shader_compiler: &Compiler,
+    ) -> Result<Pipeline, ShaderError> {
+        let mut layout = Layout::new(device);
+        match create_pipeline(device, render_pass, &layout, shader_compiler) {
+            Ok(pipeline) => Ok(Pipeline { pipeline, layout }),
+            Err(err) => {
+                layout.destroy(device);
+                Err(err)
+            }
+        }
+    }
+
+    // Shaders changed : the same pipeline with them compiled again, swapped in once the
+    // device is idle. This one is kept when they fail to compile
+    pub fn rebuild(
+        &mut self,
+        device: &Device,
+        render_pass: &ash::vk::RenderPass,
+        shader_compiler: &Compiler,
+    ) -> Result<(), ShaderError> {
+        let pipeline = create_pipeline(device, render_pass, &self.layout, shader_compiler)?;
+        unsafe {
             device
-                .create_graphics_pipelines(PipelineCache::null(), &create_info, None)
-                .expect("Failed to create graphics pipeline.")[0]
-        };
-
-        // Cleanup and return
-        unsafe { device.destroy_shader_module(vertex, None) };
-        unsafe { device.destroy_shader_module(fragment, None) };
-        Pipeline { pipeline, layout }
+                .device_wait_idle()
+                .expect("Failed to wait device idle.");
+            device.destroy_pipeline(self.pipeline, None);
+        }
+        self.pipeline = pipeline;
+        Ok(())
     }
 
-    pub fn mvp_layout(&self) -> &DescriptorSetLayout {
-        &self.layout.mvp
+    pub fn camera_layout(&self) -> &DescriptorSetLayout {
+        &self.layout.camera
     }
 
     pub fn destroy(&mut self, device: &Device) {
@@ -139,3 +79,108 @@ impl Pipeline {
         }
     }
 }
+
+// With "layout" (descriptor sets stay valid across rebuilds)
+fn create_pipeline(
+    device: &Device,
+    render_pass: &ash::vk::RenderPass,
+    layout: &Layout,
+    shader_compiler: &Compiler,
+) -> Result<ash::vk::Pipeline, ShaderError> {
+    // compiling shaders (read from files : they change without rebuilding the app)
+    let vertex = shader_compiler.to_shader_module(device, "mesh.vert", ShaderKind::Vertex)?;
+    let fragment = match shader_compiler.to_shader_module(device, "mesh.frag", ShaderKind::Fragment)
+    {
+        Ok(fragment) => fragment,
+        Err(err) => {
+            unsafe { device.destroy_shader_module(vertex, None) };
+            return Err(err);
+        }
+    };
+
+    // SPECIFY : programmable stages
+    let vertex_stage_info = PipelineShaderStageCreateInfo::default()
+        .module(vertex)
+        .stage(ShaderStageFlags::VERTEX)
+        .name(c"main");
+
+    let fragment_stage_info = PipelineShaderStageCreateInfo::default()
+        .module(fragment)
+        .stage(ShaderStageFlags::FRAGMENT)
+        .name(c"main");
+
+    let shader_stages = [vertex_stage_info, fragment_stage_info];
+
+    // SPECIFY : fixed funtions stages
+    let vertex_binding_descriptions = [Vertex::binding_description()];
+    let vertex_attribute_descriptions = Vertex::attribute_description();
+    let vertex_input_state = PipelineVertexInputStateCreateInfo::default()
+        .vertex_binding_descriptions(&vertex_binding_descriptions)
+        .vertex_attribute_descriptions(&vertex_attribute_descriptions);
+
+    let input_assembly_state = PipelineInputAssemblyStateCreateInfo::default()
+        .topology(PrimitiveTopology::TRIANGLE_LIST)
+        .primitive_restart_enable(false);
+
+    // viewport and scissor are set while recording (they follow the swapchain extent)
+    let viewport_state = PipelineViewportStateCreateInfo::default()
+        .viewport_count(1)
+        .scissor_count(1);
+    let dynamic_states = [DynamicState::VIEWPORT, DynamicState::SCISSOR];
+    let dynamic_state = PipelineDynamicStateCreateInfo::default().dynamic_states(&dynamic_states);
+
+    let rasterization_state = PipelineRasterizationStateCreateInfo::default()
+        .depth_clamp_enable(false)
+        .rasterizer_discard_enable(false)
+        .polygon_mode(PolygonMode::FILL)
+        .line_width(1.)
+        .cull_mode(CullModeFlags::BACK)
+        .front_face(FrontFace::COUNTER_CLOCKWISE)
+        .depth_bias_enable(false);
+
+    let multisample_state = PipelineMultisampleStateCreateInfo::default()
+        .sample_shading_enable(false)
+        .rasterization_samples(SampleCountFlags::TYPE_1);
+
+    let depth_stencil_state = PipelineDepthStencilStateCreateInfo::default()
+        .depth_test_enable(true)
+        .depth_write_enable(true)
+        .depth_compare_op(CompareOp::GREATER_OR_EQUAL) // reversed depth
+        .depth_bounds_test_enable(false)
+        .stencil_test_enable(false);
+
+    let color_blend_attachment = PipelineColorBlendAttachmentState::default()
+        .color_write_mask(ColorComponentFlags::RGBA)
+        .blend_enable(false);
+    let attachments = [color_blend_attachment];
+    let color_blend_state = PipelineColorBlendStateCreateInfo::default()
+        .logic_op_enable(false)
+        .attachments(&attachments);
+
+    // CREATE : pipeline
+    let pipeline_info = GraphicsPipelineCreateInfo::default()
+        .stages(&shader_stages)
+        .vertex_input_state(&vertex_input_state)
+        .input_assembly_state(&input_assembly_state)
+        .viewport_state(&viewport_state)
+        .rasterization_state(&rasterization_state)
+        .multisample_state(&multisample_state)
+        .depth_stencil_state(&depth_stencil_state)
+        .color_blend_state(&color_blend_state)
+        .dynamic_state(&dynamic_state)
+        .layout(layout.pipeline)
+        .render_pass(*render_pass)
+        .subpass(0);
+
+    let create_info = [pipeline_info];
+
+    let pipeline =
+        unsafe { device.create_graphics_pipelines(PipelineCache::null(), &create_info, None) };
+
+    // Cleanup (whether the pipeline was created or not) and return
+    unsafe { device.destroy_shader_module(vertex, None) };
+    unsafe { device.destroy_shader_module(fragment, None) };
+    pipeline
+        .map(|pipelines| pipelines[0])
+        .map_err(|(_, result)| ShaderError::Pipeline(result))
+}
@@ -2,8 +2,8 @@ use std::ops::Deref;
 
 use ash::vk::{
     AccessFlags, AttachmentDescription, AttachmentLoadOp, AttachmentReference, AttachmentStoreOp,
-    ImageLayout, PipelineBindPoint, PipelineStageFlags, RenderPassCreateInfo, SampleCountFlags,
-    SubpassDependency, SubpassDescription, SUBPASS_EXTERNAL,
+    Format, ImageLayout, PipelineBindPoint, PipelineStageFlags, RenderPassCreateInfo,
+    SampleCountFlags, SubpassDependency, SubpassDescription, SUBPASS_EXTERNAL,
 };
 
 use crate::app::graphics_engine::Device;
@@ -21,35 +21,57 @@ impl Deref for RenderPass {
 }
 
 impl RenderPass {
-    pub fn new(device: &Device) -> RenderPass {
+    // "final_layout" : what the final image is used for next (presenting, reading back)
+    pub fn new(device: &Device, format: Format, final_layout: ImageLayout) -> RenderPass {
         // Attachments
         let final_image = AttachmentDescription::default()
-            .format(device.infos.surface_format.format)
+            .format(format)
             .samples(SampleCountFlags::TYPE_1)
             .load_op(AttachmentLoadOp::CLEAR)
             .store_op(AttachmentStoreOp::STORE)
             .initial_layout(ImageLayout::UNDEFINED)
-            .final_layout(ImageLayout::PRESENT_SRC_KHR);
-        let attachments = [final_image];
+            .final_layout(final_layout);
+        let depth = AttachmentDescription::default()
+            .format(device.infos.depth_format)
+            .samples(SampleCountFlags::TYPE_1)
+            .load_op(AttachmentLoadOp::CLEAR)
+            .store_op(AttachmentStoreOp::DONT_CARE)
+            .stencil_load_op(AttachmentLoadOp::DONT_CARE)
+            .stencil_store_op(AttachmentStoreOp::DONT_CARE)
+            .initial_layout(ImageLayout::UNDEFINED)
+            .final_layout(ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL);
+        let attachments = [final_image, depth];
 
         // Subpasses
         let final_image_ref = AttachmentReference::default()
             .attachment(0)
             .layout(ImageLayout::COLOR_ATTACHMENT_OPTIMAL);
         let color_attachments = [final_image_ref];
+        let depth_ref = AttachmentReference::default()
+            .attachment(1)
+            .layout(ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL);
         let color_rendering = SubpassDescription::default()
             .pipeline_bind_point(PipelineBindPoint::GRAPHICS)
-            .color_attachments(&color_attachments);
+            .color_attachments(&color_attachments)
+            .depth_stencil_attachment(&depth_ref);
         let subpasses = [color_rendering];
 
-        // Dependencies
+        // Dependencies (depth is shared : previous depth writes must end before clearing)
         let dependency = SubpassDependency::default()
             .src_subpass(SUBPASS_EXTERNAL)
             .dst_subpass(0)
-            .src_stage_mask(PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT)
-            .src_access_mask(AccessFlags::empty())
-            .dst_stage_mask(PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT)
-            .dst_access_mask(AccessFlags::COLOR_ATTACHMENT_WRITE);
+            .src_stage_mask(
+                PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT
+                    | PipelineStageFlags::LATE_FRAGMENT_TESTS,
+            )
+            .src_access_mask(AccessFlags::DEPTH_STENCIL_ATTACHMENT_WRITE)
+            .dst_stage_mask(
+                PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT
+                    | PipelineStageFlags::EARLY_FRAGMENT_TESTS,
+            )
+            .dst_access_mask(
+                AccessFlags::COLOR_ATTACHMENT_WRITE | AccessFlags::DEPTH_STENCIL_ATTACHMENT_WRITE,
+            );
         let dependencies = [dependency];
 
         // Create Render pass
@@ -0,0 +1,265 @@
+use std::collections::{HashMap, HashSet};
+
+use ash::vk::{BufferCopy, CommandBuffer, Fence, Queue, Semaphore, SubmitInfo};
+
+use crate::app::graphics_engine::{
+    device::{Buffer, MappedBuffer},
+    mesher::{Mesh, MeshKey, Vertex},
+    Device,
+};
+
+use super::{
+    commands::{self, BufferCopies},
+    resources::{self, MeshRange, INITIAL_INDICES, INITIAL_VERTICES},
+};
+
+// Meshes uploaded once to device-local buffers shared by every frame, found back by their key.
+// Meshes are appended after the previous ones. When the buffers are full (or mostly invalidated)
+// they are reallocated, keeping only the meshes still drawn (the others are uploaded again if
+// they come back). The old buffers are destroyed once no frame in flight may read them.
+pub struct MeshRegistry {
+    // Resources
+    pub vertices: Buffer,
+    pub indices: Buffer,
+    vertex_capacity: u64,
+    index_capacity: u64,
+    vertex_count: u64,
+    index_count: u64,
+    // Elements of invalidated meshes, still taking space until the next reallocation
+    stale_vertices: u64,
+    stale_indices: u64,
+    ranges: HashMap<MeshKey, MeshRange>,
+    // Replaced buffers, with the number of frames to start before they can be destroyed
+    retired: Vec<(Buffer, usize)>,
+    frames_in_flight: usize,
+}
+
+impl MeshRegistry {
+    pub fn new(device: &Device, frames_in_flight: usize) -> MeshRegistry {
+        MeshRegistry {
+            vertices: resources::allocate_vertices(device, INITIAL_VERTICES),
+            indices: resources::allocate_indices(device, INITIAL_INDICES),
+            vertex_capacity: INITIAL_VERTICES,
+            index_capacity: INITIAL_INDICES,
+            vertex_count: 0,
+            index_count: 0,
+            stale_vertices: 0,
+            stale_indices: 0,
+            ranges: HashMap::new(),
+            retired: Vec::new(),
+            frames_in_flight,
+        }
+    }
+
+    // The device must be idle
+    pub fn destroy(&mut self, device: &Device) {
+        for (mut buffer, _) in self.retired.drain(..) {
+            device.ct_destroy_buffer(&mut buffer);
+        }
+        device.ct_destroy_buffer(&mut self.vertices);
+        device.ct_destroy_buffer(&mut self.indices);
+    }
+
+    pub fn contains(&self, key: &MeshKey) -> bool {
+        self.ranges.contains_key(key)
+    }
+
+    pub fn range(&self, key: &MeshKey) -> MeshRange {
+        self.ranges[key]
+    }
+
+    // The geometry of "key" won't be drawn again : its space is reclaimed by the next reallocation
+    // (frames in flight may still draw it until then)
+    pub fn invalidate(&mut self, key: &MeshKey) {
+        if let Some(range) = self.ranges.remove(key) {
+            self.stale_vertices += range.vertex_count as u64;
+            self.stale_indices += range.index_count as u64;
+        }
+    }
+
+    // More than half of the meshes were invalidated : worth reallocating even without new meshes
+    pub fn wasteful(&self) -> bool {
+        2 * self.stale_vertices > self.vertex_count || 2 * self.stale_indices > self.index_count
+    }
+
+    // A frame was started : its last rendering is done. Frames are started in turn, so retired
+    // buffers no frame reads anymore after "frames_in_flight" starts are destroyed
+    pub fn frame_started(&mut self, device: &Device) {
+        for (_, frames_left) in &mut self.retired {
+            *frames_left -= 1;
+        }
+        for (mut buffer, _) in self
+            .retired
+            .extract_if(.., |(_, frames_left)| *frames_left == 0)
+        {
+            device.ct_destroy_buffer(&mut buffer);
+        }
+    }
+
+    // Record the copies of "meshes" (not registered yet) in "transfer" and submit it,
+    // "drawn" : keys to keep if the buffers are reallocated.
+    // Signals "upload_done" : the frame's draw must wait on it (it is on another queue).
+    // Returns the staging buffers, to keep until the frame's rendering is done
+    pub fn upload(
+        &mut self,
+        device: &Device,
+        transfer_queue: Queue,
+        transfer: CommandBuffer,
+        upload_done: Semaphore,
+        meshes: &[(MeshKey, Mesh)],
+        drawn: &HashSet<MeshKey>,
+    ) -> [MappedBuffer; 2] {
+        let new_vertices: u64 = meshes.iter().map(|(_, m)| m.vertices.len() as u64).sum();
+        let new_indices: u64 = meshes.iter().map(|(_, m)| m.indices.len() as u64).sum();
+
+        // Full or wasteful : move the meshes still drawn to the start of new buffers
+        let mut copies = Vec::new();
+        let reallocating = self.vertex_count + new_vertices > self.vertex_capacity
+            || self.index_count + new_indices > self.index_capacity
+            || self.wasteful();
+        if reallocating {
+            let (mut vertices, mut indices, compaction) =
+                self.compact(device, drawn, new_vertices, new_indices);
+            std::mem::swap(&mut self.vertices, &mut vertices);
+            std::mem::swap(&mut self.indices, &mut indices);
+            copies.extend(compaction);
+            // Frames in flight may still read the old buffers, and this frame copies from them
+            self.retired.push((vertices, self.frames_in_flight));
+            self.retired.push((indices, self.frames_in_flight));
+        }
+
+        // New meshes : staged then appended
+        let staging_vertices = resources::allocate_staging_vertices(device, new_vertices.max(1));
+        let staging_indices = resources::allocate_staging_indices(device, new_indices.max(1));
+        let mut vertex_regions = Vec::with_capacity(meshes.len());
+        let mut index_regions = Vec::with_capacity(meshes.len());
+        let (mut staged_vertices, mut staged_indices) = (0, 0);
+        for (key, mesh) in meshes {
+            let range = MeshRange {
+                vertex_offset: self.vertex_count as i32,
+                vertex_count: mesh.vertices.len() as u32,
+                first_index: self.index_count as u32,
+                index_count: mesh.indices.len() as u32,
+            };
+            unsafe {
+                staging_vertices
+                    .ptr
+                    .add(Vertex::size_of() * staged_vertices)
+                    .copy_from(
+                        mesh.vertices.as_ptr() as *const u8,
+                        Vertex::size_of() * mesh.vertices.len(),
+                    );
+                staging_indices
+                    .ptr
+                    .add(size_of::<u32>() * staged_indices)
+                    .copy_from(
+                        mesh.indices.as_ptr() as *const u8,
+                        size_of::<u32>() * mesh.indices.len(),
+                    );
+            }
+            vertex_regions.push(vertex_region(
+                staged_vertices as u64,
+                range.vertex_offset as u64,
+                range.vertex_count as u64,
+            ));
+            index_regions.push(index_region(
+                staged_indices as u64,
+                range.first_index as u64,
+                range.index_count as u64,
+            ));
+            staged_vertices += mesh.vertices.len();
+            staged_indices += mesh.indices.len();
+            self.vertex_count += range.vertex_count as u64;
+            self.index_count += range.index_count as u64;
+            self.ranges.insert(*key, range);
+        }
+        copies.push((*staging_vertices, *self.vertices, vertex_regions));
+        copies.push((*staging_indices, *self.indices, index_regions));
+
+        // Record and submit : the frame's fence covers it, as its draw waits on "upload_done".
+        // Reallocating reads the old buffers, which earlier uploads may have just written
+        commands::record_copies(device, transfer, &copies, reallocating);
+        let command_buffers = [transfer];
+        let signal_semaphores = [upload_done];
+        let submit_info = SubmitInfo::default()
+            .command_buffers(&command_buffers)
+            .signal_semaphores(&signal_semaphores);
+        unsafe {
+            device
+                .queue_submit(transfer_queue, &[submit_info], Fence::null())
+                .expect("Failed to submit upload.");
+        }
+
+        [staging_vertices, staging_indices]
+    }
+
+    // New buffers big enough for the meshes kept and the new ones, and the copies moving
+    // the kept meshes at their start (ranges are updated)
+    fn compact(
+        &mut self,
+        device: &Device,
+        drawn: &HashSet<MeshKey>,
+        new_vertices: u64,
+        new_indices: u64,
+    ) -> (Buffer, Buffer, [BufferCopies; 2]) {
+        self.ranges.retain(|key, _| drawn.contains(key));
+
+        let mut vertex_regions = Vec::with_capacity(self.ranges.len());
+        let mut index_regions = Vec::with_capacity(self.ranges.len());
+        let (mut vertex_count, mut index_count) = (0, 0);
+        for range in self.ranges.values_mut() {
+            vertex_regions.push(vertex_region(
+                range.vertex_offset as u64,
+                vertex_count,
+                range.vertex_count as u64,
+            ));
+            index_regions.push(index_region(
+                range.first_index as u64,
+                index_count,
+                range.index_count as u64,
+            ));
+            range.vertex_offset = vertex_count as i32;
+            range.first_index = index_count as u32;
+            vertex_count += range.vertex_count as u64;
+            index_count += range.index_count as u64;
+        }
+
+        let required_vertices = vertex_count + new_vertices;
+        let required_indices = index_count + new_indices;
+        if required_vertices > self.vertex_capacity {
+            self.vertex_capacity =
+                resources::grown_capacity(self.vertex_capacity, required_vertices);
+        }
+        if required_indices > self.index_capacity {
+            self.index_capacity = resources::grown_capacity(self.index_capacity, required_indices);
+        }
+        self.vertex_count = vertex_count;
+        self.index_count = index_count;
+        self.stale_vertices = 0;
+        self.stale_indices = 0;
+
+        let vertices = resources::allocate_vertices(device, self.vertex_capacity);
+        let indices = resources::allocate_indices(device, self.index_capacity);
+        let copies = [
+            (*self.vertices, *vertices, vertex_regions),
+            (*self.indices, *indices, index_regions),
+        ];
+        (vertices, indices, copies)
+    }
+}
+
+fn vertex_region(src: u64, dst: u64, count: u64) -> BufferCopy {
+    let size = Vertex::size_of() as u64;
+    BufferCopy::default()
+        .src_offset(src * size)
+        .dst_offset(dst * size)
+        .size(count * size)
+}
+
+fn index_region(src: u64, dst: u64, count: u64) -> BufferCopy {
+    let size = size_of::<u32>() as u64;
+    BufferCopy::default()
+        .src_offset(src * size)
+        .dst_offset(dst * size)
+        .size(count * size)
+}
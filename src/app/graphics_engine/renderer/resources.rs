@@ -1,11 +1,21 @@
-mod mvp;
+mod depth;
+mod readback;
 mod swapchain_images;
+mod texture;
+mod uniforms;
 mod vertices;
 
 pub use vertices::{
     allocate_indices, allocate_staging_indices, allocate_staging_vertices, allocate_vertices,
+    grown_capacity, MeshRange, INITIAL_INDICES, INITIAL_VERTICES,
 };
 
 pub use swapchain_images::create_swapchain_image_views;
 
-pub use mvp::{allocate_mvp, MVP};
+pub use uniforms::{allocate_uniforms, FrameUniforms, ObjectConstants};
+
+pub use texture::{allocate_staging_pixels, allocate_texture, create_sampler, create_texture_view};
+
+pub use depth::{allocate_depth, create_depth_view};
+
+pub use readback::allocate_readback;
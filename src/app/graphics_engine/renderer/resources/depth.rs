@@ -0,0 +1,55 @@
+use ash::vk::{
+    Extent2D, Extent3D, ImageAspectFlags, ImageCreateInfo, ImageLayout, ImageSubresourceRange,
+    ImageTiling, ImageType, ImageUsageFlags, ImageView, ImageViewCreateInfo, ImageViewType,
+    MemoryPropertyFlags, SampleCountFlags, SharingMode,
+};
+use vk_mem::AllocationCreateInfo;
+
+use crate::app::graphics_engine::{device::Image, Device};
+
+// Depth image covering "extent", only used as attachment by the graphics queue
+pub fn allocate_depth(device: &Device, extent: Extent2D) -> Image {
+    let queue_family_indices = [device.infos.graphics_idx];
+    let image_info = ImageCreateInfo::default()
+        .image_type(ImageType::TYPE_2D)
+        .format(device.infos.depth_format)
+        .extent(Extent3D::from(extent).depth(1))
+        .mip_levels(1)
+        .array_layers(1)
+        .samples(SampleCountFlags::TYPE_1)
+        .tiling(ImageTiling::OPTIMAL)
+        .usage(ImageUsageFlags::DEPTH_STENCIL_ATTACHMENT)
+        .queue_family_indices(&queue_family_indices)
+        .sharing_mode(SharingMode::EXCLUSIVE)
+        .initial_layout(ImageLayout::UNDEFINED);
+
+    let create_info = AllocationCreateInfo {
+        required_flags: MemoryPropertyFlags::DEVICE_LOCAL,
+        ..Default::default()
+    };
+
+    device.ct_create_image(&image_info, &create_info)
+}
+
+pub fn create_depth_view(device: &Device, depth: &Image) -> ImageView {
+    let subresource_range = ImageSubresourceRange::default()
+        .aspect_mask(ImageAspectFlags::DEPTH)
+        .base_mip_level(0)
+        .level_count(1)
+        .base_array_layer(0)
+        .layer_count(1);
+
+    let create_info = ImageViewCreateInfo::default()
+        // view restrictions
+        .image(**depth)
+        .view_type(ImageViewType::TYPE_2D)
+        .subresource_range(subresource_range)
+        // data interpretation
+        .format(device.infos.depth_format);
+
+    unsafe {
+        device
+            .create_image_view(&create_info, None)
+            .expect("Failed to create depth image view.")
+    }
+}
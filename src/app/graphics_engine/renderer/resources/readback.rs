@@ -0,0 +1,23 @@
+use ash::vk::{BufferCreateInfo, BufferUsageFlags, Extent2D, MemoryPropertyFlags, SharingMode};
+use vk_mem::AllocationCreateInfo;
+
+use crate::app::graphics_engine::{device::MappedBuffer, Device};
+
+// Host buffer receiving an image covering "extent" ("pixel_size" bytes per pixel),
+// copied by the graphics queue
+pub fn allocate_readback(device: &Device, extent: Extent2D, pixel_size: u64) -> MappedBuffer {
+    let size = pixel_size * extent.width as u64 * extent.height as u64;
+    let queue_family_indices = [device.infos.graphics_idx];
+    let buffer_info = BufferCreateInfo::default()
+        .queue_family_indices(&queue_family_indices)
+        .sharing_mode(SharingMode::EXCLUSIVE)
+        .size(size)
+        .usage(BufferUsageFlags::TRANSFER_DST);
+
+    let create_info = AllocationCreateInfo {
+        required_flags: MemoryPropertyFlags::HOST_VISIBLE | MemoryPropertyFlags::HOST_COHERENT,
+        ..Default::default()
+    };
+
+    device.ct_create_mapped_buffer(&buffer_info, &create_info)
+}
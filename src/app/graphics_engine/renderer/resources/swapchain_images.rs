@@ -1,23 +1,24 @@
 use ash::vk::{
-    ComponentMapping, Image, ImageAspectFlags, ImageSubresourceRange, ImageView,
+    ComponentMapping, Format, Image, ImageAspectFlags, ImageSubresourceRange, ImageView,
     ImageViewCreateInfo, ImageViewType,
 };
 
 use crate::app::graphics_engine::Device;
 
+// Also used for the offscreen target, which stands for a swapchain of one image
 pub fn create_swapchain_image_views(
     device: &Device,
-    swapchain_images: &Vec<Image>,
+    swapchain_images: &[Image],
+    format: Format,
 ) -> Vec<ImageView> {
     swapchain_images
         .iter()
-        .map(|image| create_swapchain_image_view(device, image))
+        .map(|image| create_swapchain_image_view(device, image, format))
         .collect()
 }
 
-fn create_swapchain_image_view(device: &Device, image: &Image) -> ImageView {
+fn create_swapchain_image_view(device: &Device, image: &Image, format: Format) -> ImageView {
     let components = ComponentMapping::default(); // identity
-    let format = device.infos.surface_format.format;
 
     let subresource_range = ImageSubresourceRange::default()
         .aspect_mask(ImageAspectFlags::COLOR)
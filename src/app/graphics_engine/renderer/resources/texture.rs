@@ -0,0 +1,101 @@
+use ash::vk::{
+    BorderColor, BufferCreateInfo, BufferUsageFlags, CompareOp, Extent3D, Filter, Format,
+    ImageAspectFlags, ImageCreateInfo, ImageLayout, ImageSubresourceRange, ImageTiling, ImageType,
+    ImageUsageFlags, ImageView, ImageViewCreateInfo, ImageViewType, MemoryPropertyFlags,
+    SampleCountFlags, Sampler, SamplerAddressMode, SamplerCreateInfo, SamplerMipmapMode,
+    SharingMode,
+};
+use vk_mem::AllocationCreateInfo;
+
+use crate::app::graphics_engine::{
+    device::{Image, MappedBuffer},
+    Device,
+};
+
+// Textures are 8 bits RGBA, sRGB encoded : sampling gives linear colors
+pub const TEXTURE_FORMAT: Format = Format::R8G8B8A8_SRGB;
+
+// Sampled image, copied to by the graphics queue
+pub fn allocate_texture(device: &Device, width: u32, height: u32) -> Image {
+    let queue_family_indices = [device.infos.graphics_idx];
+    let image_info = ImageCreateInfo::default()
+        .image_type(ImageType::TYPE_2D)
+        .format(TEXTURE_FORMAT)
+        .extent(Extent3D::default().width(width).height(height).depth(1))
+        .mip_levels(1)
+        .array_layers(1)
+        .samples(SampleCountFlags::TYPE_1)
+        .tiling(ImageTiling::OPTIMAL)
+        .usage(ImageUsageFlags::TRANSFER_DST | ImageUsageFlags::SAMPLED)
+        .queue_family_indices(&queue_family_indices)
+        .sharing_mode(SharingMode::EXCLUSIVE)
+        .initial_layout(ImageLayout::UNDEFINED);
+
+    let create_info = AllocationCreateInfo {
+        required_flags: MemoryPropertyFlags::DEVICE_LOCAL,
+        ..Default::default()
+    };
+
+    device.ct_create_image(&image_info, &create_info)
+}
+
+pub fn create_texture_view(device: &Device, texture: &Image) -> ImageView {
+    let subresource_range = ImageSubresourceRange::default()
+        .aspect_mask(ImageAspectFlags::COLOR)
+        .base_mip_level(0)
+        .level_count(1)
+        .base_array_layer(0)
+        .layer_count(1);
+
+    let create_info = ImageViewCreateInfo::default()
+        .image(**texture)
+        .view_type(ImageViewType::TYPE_2D)
+        .subresource_range(subresource_range)
+        .format(TEXTURE_FORMAT);
+
+    unsafe {
+        device
+            .create_image_view(&create_info, None)
+            .expect("Failed to create texture image view.")
+    }
+}
+
+// Host buffer holding "size" bytes of pixels to copy to a texture
+pub fn allocate_staging_pixels(device: &Device, size: u64) -> MappedBuffer {
+    let queue_family_indices = [device.infos.graphics_idx];
+    let buffer_info = BufferCreateInfo::default()
+        .queue_family_indices(&queue_family_indices)
+        .sharing_mode(SharingMode::EXCLUSIVE)
+        .size(size)
+        .usage(BufferUsageFlags::TRANSFER_SRC);
+
+    let create_info = AllocationCreateInfo {
+        required_flags: MemoryPropertyFlags::HOST_VISIBLE | MemoryPropertyFlags::HOST_COHERENT,
+        ..Default::default()
+    };
+
+    device.ct_create_mapped_buffer(&buffer_info, &create_info)
+}
+
+// Linear filtering, repeating uvs (glTF's default wrapping)
+pub fn create_sampler(device: &Device) -> Sampler {
+    let create_info = SamplerCreateInfo::default()
+        .mag_filter(Filter::LINEAR)
+        .min_filter(Filter::LINEAR)
+        .mipmap_mode(SamplerMipmapMode::NEAREST)
+        .address_mode_u(SamplerAddressMode::REPEAT)
+        .address_mode_v(SamplerAddressMode::REPEAT)
+        .address_mode_w(SamplerAddressMode::REPEAT)
+        .anisotropy_enable(false)
+        .compare_enable(false)
+        .compare_op(CompareOp::ALWAYS)
+        .border_color(BorderColor::INT_OPAQUE_BLACK)
+        .min_lod(0.)
+        .max_lod(0.);
+
+    unsafe {
+        device
+            .create_sampler(&create_info, None)
+            .expect("Failed to create sampler.")
+    }
+}
@@ -0,0 +1,94 @@
+use ash::vk::{BufferCreateInfo, BufferUsageFlags, Extent2D, MemoryPropertyFlags, SharingMode};
+use glam::{Mat4, Vec3, Vec4};
+use vk_mem::AllocationCreateInfo;
+
+use crate::app::{
+    graphics_engine::{device::MappedBuffer, Device},
+    model::{Camera, Lighting, MAX_LIGHTS},
+};
+
+// A light as the shaders read it (std140 : vec4s, the w components unused)
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+struct LightUniform {
+    direction: Vec4,
+    color: Vec4,
+}
+
+// View, projection and lighting, shared by every object of a frame
+// (model matrices are pushed per draw)
+#[repr(C)]
+pub struct FrameUniforms {
+    view: Mat4,
+    proj: Mat4,
+    ambient: Vec4,
+    lights: [LightUniform; MAX_LIGHTS],
+    light_count: u32,
+}
+
+impl FrameUniforms {
+    pub fn new(camera: &Camera, lighting: &Lighting, extent: Extent2D) -> FrameUniforms {
+        let view = camera.view_matrix();
+        let aspect_ratio = extent.width as f32 / extent.height as f32;
+        let proj = camera.projection_matrix(aspect_ratio);
+
+        let mut lights = [LightUniform::default(); MAX_LIGHTS];
+        for (uniform, light) in lights.iter_mut().zip(&lighting.lights) {
+            *uniform = LightUniform {
+                direction: light.direction.normalize_or_zero().extend(0.),
+                color: light.color.extend(0.),
+            };
+        }
+        FrameUniforms {
+            view,
+            proj,
+            ambient: lighting.ambient.extend(0.),
+            lights,
+            light_count: lighting.lights.len().min(MAX_LIGHTS) as u32,
+        }
+    }
+    pub fn size_of() -> usize {
+        size_of::<FrameUniforms>()
+    }
+}
+
+// Pushed for each object drawn : its model matrix and its material's base color
+// (w unused)
+#[repr(C)]
+pub struct ObjectConstants {
+    model: Mat4,
+    base_color: Vec4,
+}
+
+impl ObjectConstants {
+    pub fn new(model: Mat4, base_color: Vec3) -> ObjectConstants {
+        ObjectConstants {
+            model,
+            base_color: base_color.extend(1.),
+        }
+    }
+    pub fn size_of() -> usize {
+        size_of::<ObjectConstants>()
+    }
+    pub fn as_bytes(&self) -> &[u8] {
+        let ptr: *const ObjectConstants = self;
+        unsafe { std::slice::from_raw_parts(ptr as *const u8, ObjectConstants::size_of()) }
+    }
+}
+
+pub fn allocate_uniforms(device: &Device) -> MappedBuffer {
+    let size = FrameUniforms::size_of() as u64;
+    let queue_family_indices = [device.infos.graphics_idx];
+    let buffer_info = BufferCreateInfo::default()
+        .queue_family_indices(&queue_family_indices)
+        .sharing_mode(SharingMode::EXCLUSIVE)
+        .size(size)
+        .usage(BufferUsageFlags::UNIFORM_BUFFER);
+
+    let create_info = AllocationCreateInfo {
+        required_flags: MemoryPropertyFlags::HOST_VISIBLE | MemoryPropertyFlags::HOST_COHERENT,
+        ..Default::default()
+    };
+
+    device.ct_create_mapped_buffer(&buffer_info, &create_info)
+}
@@ -2,18 +2,42 @@ use ash::vk::{BufferCreateInfo, BufferUsageFlags, MemoryPropertyFlags, SharingMo
 use vk_mem::AllocationCreateInfo;
 
 use crate::app::graphics_engine::{
-    device::Buffer,
-    mesher::{Vertex, MAX_INDICES, MAX_VERTICES},
+    device::{Buffer, MappedBuffer},
+    mesher::Vertex,
     Device,
 };
 
-pub fn allocate_vertices(device: &Device) -> Buffer {
-    let queue_family_indices = [device.infos.graphics_idx, device.infos.transfer_idx];
+// Capacities (in elements) of the first buffers, they grow when meshes need more
+// (device buffers are also copy sources : growing copies the meshes still in use)
+pub const INITIAL_VERTICES: u64 = 1 << 16;
+pub const INITIAL_INDICES: u64 = 1 << 18;
+
+// Where a mesh lies in the vertex and index buffers
+#[derive(Clone, Copy)]
+pub struct MeshRange {
+    pub vertex_offset: i32,
+    pub vertex_count: u32,
+    pub first_index: u32,
+    pub index_count: u32,
+}
+
+// Growth policy : at least double, rounded to the next power of 2 holding "required"
+pub fn grown_capacity(capacity: u64, required: u64) -> u64 {
+    required.next_power_of_two().max(2 * capacity)
+}
+
+pub fn allocate_vertices(device: &Device, capacity: u64) -> Buffer {
+    let (sharing_mode, queue_family_indices) =
+        device.bp_sharing(&[device.infos.graphics_idx, device.infos.transfer_idx]);
     let buffer_info = BufferCreateInfo::default()
         .queue_family_indices(&queue_family_indices)
-        .sharing_mode(SharingMode::CONCURRENT)
-        .size(size_of::<Vertex>() as u64 * MAX_VERTICES)
-        .usage(BufferUsageFlags::VERTEX_BUFFER | BufferUsageFlags::TRANSFER_DST);
+        .sharing_mode(sharing_mode)
+        .size(size_of::<Vertex>() as u64 * capacity)
+        .usage(
+            BufferUsageFlags::VERTEX_BUFFER
+                | BufferUsageFlags::TRANSFER_SRC
+                | BufferUsageFlags::TRANSFER_DST,
+        );
 
     let create_info = AllocationCreateInfo {
         required_flags: MemoryPropertyFlags::DEVICE_LOCAL,
@@ -23,12 +47,12 @@ pub fn allocate_vertices(device: &Device) -> Buffer {
     device.ct_create_buffer(&buffer_info, &create_info)
 }
 
-pub fn allocate_staging_vertices(device: &Device) -> Buffer {
+pub fn allocate_staging_vertices(device: &Device, capacity: u64) -> MappedBuffer {
     let queue_family_indices = [device.infos.transfer_idx];
     let buffer_info = BufferCreateInfo::default()
         .queue_family_indices(&queue_family_indices)
         .sharing_mode(SharingMode::EXCLUSIVE)
-        .size(size_of::<Vertex>() as u64 * MAX_VERTICES)
+        .size(size_of::<Vertex>() as u64 * capacity)
         .usage(BufferUsageFlags::TRANSFER_SRC);
 
     let create_info = AllocationCreateInfo {
@@ -36,16 +60,21 @@ pub fn allocate_staging_vertices(device: &Device) -> Buffer {
         ..Default::default()
     };
 
-    device.ct_create_buffer(&buffer_info, &create_info)
+    device.ct_create_mapped_buffer(&buffer_info, &create_info)
 }
 
-pub fn allocate_indices(device: &Device) -> Buffer {
-    let queue_family_indices = [device.infos.graphics_idx, device.infos.transfer_idx];
+pub fn allocate_indices(device: &Device, capacity: u64) -> Buffer {
+    let (sharing_mode, queue_family_indices) =
+        device.bp_sharing(&[device.infos.graphics_idx, device.infos.transfer_idx]);
     let buffer_info = BufferCreateInfo::default()
         .queue_family_indices(&queue_family_indices)
-        .sharing_mode(SharingMode::CONCURRENT)
-        .size(size_of::<u32>() as u64 * MAX_INDICES)
-        .usage(BufferUsageFlags::INDEX_BUFFER | BufferUsageFlags::TRANSFER_DST);
+        .sharing_mode(sharing_mode)
+        .size(size_of::<u32>() as u64 * capacity)
+        .usage(
+            BufferUsageFlags::INDEX_BUFFER
+                | BufferUsageFlags::TRANSFER_SRC
+                | BufferUsageFlags::TRANSFER_DST,
+        );
 
     let create_info = AllocationCreateInfo {
         required_flags: MemoryPropertyFlags::DEVICE_LOCAL,
@@ -55,12 +84,12 @@ pub fn allocate_indices(device: &Device) -> Buffer {
     device.ct_create_buffer(&buffer_info, &create_info)
 }
 
-pub fn allocate_staging_indices(device: &Device) -> Buffer {
+pub fn allocate_staging_indices(device: &Device, capacity: u64) -> MappedBuffer {
     let queue_family_indices = [device.infos.transfer_idx];
     let buffer_info = BufferCreateInfo::default()
         .queue_family_indices(&queue_family_indices)
         .sharing_mode(SharingMode::EXCLUSIVE)
-        .size(size_of::<u32>() as u64 * MAX_INDICES)
+        .size(size_of::<u32>() as u64 * capacity)
         .usage(BufferUsageFlags::TRANSFER_SRC);
 
     let create_info = AllocationCreateInfo {
@@ -68,5 +97,5 @@ pub fn allocate_staging_indices(device: &Device) -> Buffer {
         ..Default::default()
     };
 
-    device.ct_create_buffer(&buffer_info, &create_info)
+    device.ct_create_mapped_buffer(&buffer_info, &create_info)
 }
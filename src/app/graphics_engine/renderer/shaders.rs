@@ -1,5 +1,44 @@
 mod compiler;
-mod fragment;
-mod vertex;
+mod source;
+#[cfg(test)]
+mod tests;
 
-pub use compiler::Compiler;
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+pub use compiler::{Compiler, ShaderError};
+
+// Where shaders are read from : $SHADER_DIR, or the first "shaders" directory found beside the
+// executable (when copied there with it), in the crate it was built from, in the working directory
+pub fn shader_directory() -> PathBuf {
+    if let Some(directory) = env::var_os("SHADER_DIR") {
+        return PathBuf::from(directory);
+    }
+    let beside_exe = env::current_exe()
+        .ok()
+        .and_then(|exe| Some(exe.parent()?.join("shaders")));
+    let in_crate = PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/shaders"));
+    beside_exe
+        .into_iter()
+        .chain([in_crate])
+        .find(|directory| directory.is_dir())
+        .unwrap_or_else(|| PathBuf::from("shaders"))
+}
+
+// Files of "directory" and its subdirectories : what its shaders can be built from
+pub fn shader_files(directory: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(directory) else {
+        return Vec::new();
+    };
+    let mut files = Vec::new();
+    for path in entries.flatten().map(|entry| entry.path()) {
+        if path.is_dir() {
+            files.extend(shader_files(&path));
+        } else {
+            files.push(path);
+        }
+    }
+    files
+}
@@ -1,46 +1,118 @@
-use std::ops::Deref;
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
 
 use ash::vk::{ShaderModule, ShaderModuleCreateInfo};
-use shaderc::ShaderKind;
+use shaderc::{CompileOptions, ShaderKind};
 
 use crate::app::graphics_engine::Device;
 
-pub struct Compiler {
-    compiler: shaderc::Compiler,
+use super::source::{find_source, read_spirv, resolve_include, ShaderSource};
+
+#[derive(Debug)]
+pub enum ShaderError {
+    // Neither the GLSL nor its SPIR-V
+    Missing(PathBuf),
+    Io(PathBuf, io::Error),
+    // shaderc's diagnostics
+    Compilation(String),
+    Module(ash::vk::Result),
+    // The driver failed to build the pipeline from the shader modules
+    Pipeline(ash::vk::Result),
 }
 
-impl Deref for Compiler {
-    type Target = shaderc::Compiler;
-    fn deref(&self) -> &Self::Target {
-        &self.compiler
+impl fmt::Display for ShaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShaderError::Missing(path) => {
+                write!(f, "{} : no such shader (nor its .spv)", path.display())
+            }
+            ShaderError::Io(path, err) => write!(f, "{} : {err}", path.display()),
+            ShaderError::Compilation(diagnostics) => write!(f, "{diagnostics}"),
+            ShaderError::Module(result) => write!(f, "Failed to create shader module : {result}"),
+            ShaderError::Pipeline(result) => {
+                write!(f, "Failed to create graphics pipeline : {result}")
+            }
+        }
     }
 }
 
+impl std::error::Error for ShaderError {}
+
+// Shaders of a directory, GLSL compiled with "defines" and #include resolved from there
+pub struct Compiler {
+    compiler: shaderc::Compiler,
+    directory: PathBuf,
+    defines: Vec<(String, String)>,
+}
+
 impl Compiler {
-    pub fn new() -> Compiler {
-        let compiler = shaderc::Compiler::new().unwrap();
-        Compiler { compiler }
+    pub fn new(directory: PathBuf) -> Compiler {
+        let compiler = shaderc::Compiler::new().expect("Failed to create shader compiler.");
+        Compiler {
+            compiler,
+            directory,
+            defines: Vec::new(),
+        }
+    }
+
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    // #define "name" "value" before compiling
+    pub fn define(&mut self, name: &str, value: &str) {
+        self.defines.push((name.to_string(), value.to_string()));
+    }
+
+    // SPIR-V of "name" in the directory, see "find_source"
+    pub fn compile(&self, name: &str, shader_kind: ShaderKind) -> Result<Vec<u32>, ShaderError> {
+        let path = match find_source(&self.directory, name)? {
+            ShaderSource::Spirv(path) => return read_spirv(&path),
+            ShaderSource::Glsl(path) => path,
+        };
+        let source_text =
+            fs::read_to_string(&path).map_err(|err| ShaderError::Io(path.clone(), err))?;
+
+        let mut options = CompileOptions::new().expect("Failed to create shader compile options.");
+        for (name, value) in &self.defines {
+            options.add_macro_definition(name, Some(value));
+        }
+        let directory = self.directory.clone();
+        options.set_include_callback(move |requested, include_type, requesting, _depth| {
+            resolve_include(&directory, requested, include_type, requesting)
+        });
+
+        // Named by its path : diagnostics tell where, relative includes are found next to it
+        let binary_result = self
+            .compiler
+            .compile_into_spirv(
+                &source_text,
+                shader_kind,
+                &path.to_string_lossy(),
+                "main",
+                Some(&options),
+            )
+            .map_err(|err| ShaderError::Compilation(err.to_string()))?;
+        if binary_result.get_num_warnings() > 0 {
+            eprint!("{}", binary_result.get_warning_messages());
+        }
+        Ok(binary_result.as_binary().to_vec())
     }
 
     pub fn to_shader_module(
         &self,
         device: &Device,
-        source_text: &str,
+        name: &str,
         shader_kind: ShaderKind,
-        input_file_name: &str,
-    ) -> ShaderModule {
-        // Compile
-        let binary_result = self
-            .compile_into_spirv(source_text, shader_kind, input_file_name, "main", None)
-            .unwrap();
-        let code = binary_result.as_binary(); // points to binary_result
-
-        // Create shader module
-        let create_info = ShaderModuleCreateInfo::default().code(code);
+    ) -> Result<ShaderModule, ShaderError> {
+        let code = self.compile(name, shader_kind)?;
+        let create_info = ShaderModuleCreateInfo::default().code(&code);
         unsafe {
             device
                 .create_shader_module(&create_info, None)
-                .expect("Failed to create shader module")
+                .map_err(ShaderError::Module)
         }
     }
 }
@@ -0,0 +1,90 @@
+use std::{
+    fs::{self, File},
+    io,
+    path::{Path, PathBuf},
+};
+
+use shaderc::{IncludeType, ResolvedInclude};
+
+use super::ShaderError;
+
+const SPIRV_MAGIC: u32 = 0x0723_0203;
+// Magic number, version, generator, ids bound, schema
+const SPIRV_HEADER_WORDS: usize = 5;
+
+// A shader as found in the shader directory
+#[derive(PartialEq, Debug)]
+pub enum ShaderSource {
+    Glsl(PathBuf),
+    // Precompiled
+    Spirv(PathBuf),
+}
+
+// "name" ("mesh.vert") : its GLSL when it's there, its SPIR-V ("mesh.vert.spv") otherwise
+pub fn find_source(directory: &Path, name: &str) -> Result<ShaderSource, ShaderError> {
+    let glsl = directory.join(name);
+    if glsl.is_file() {
+        return Ok(ShaderSource::Glsl(glsl));
+    }
+    let spirv = directory.join(format!("{name}.spv"));
+    if spirv.is_file() {
+        return Ok(ShaderSource::Spirv(spirv));
+    }
+    Err(ShaderError::Missing(glsl))
+}
+
+// Words of a SPIR-V binary, checked for its header and the word counts of its instructions
+// (a truncated or corrupted file isn't handed to the driver)
+pub fn read_spirv(path: &Path) -> Result<Vec<u32>, ShaderError> {
+    let io_error = |err| ShaderError::Io(path.to_path_buf(), err);
+    let mut file = File::open(path).map_err(io_error)?;
+    let words = ash::util::read_spv(&mut file).map_err(io_error)?;
+    check_spirv(&words)
+        .map_err(|message| io_error(io::Error::new(io::ErrorKind::InvalidData, message)))?;
+    Ok(words)
+}
+
+// After the header, instructions start with their word count (in the high half of their
+// first word) and end exactly with the binary
+fn check_spirv(words: &[u32]) -> Result<(), String> {
+    if words.len() < SPIRV_HEADER_WORDS || words[0] != SPIRV_MAGIC {
+        return Err("not SPIR-V (no magic number)".to_string());
+    }
+    if words[3] == 0 {
+        return Err("invalid SPIR-V (ids bound of 0)".to_string());
+    }
+    let mut offset = SPIRV_HEADER_WORDS;
+    while offset < words.len() {
+        let word_count = (words[offset] >> 16) as usize;
+        if word_count == 0 || offset + word_count > words.len() {
+            return Err(format!(
+                "invalid SPIR-V (truncated instruction at word {offset})"
+            ));
+        }
+        offset += word_count;
+    }
+    Ok(())
+}
+
+// The file of an #include "requested" written in "requesting" : next to it for "...",
+// in "directory" for <...> (and for "..." not found next to it)
+pub fn resolve_include(
+    directory: &Path,
+    requested: &str,
+    include_type: IncludeType,
+    requesting: &str,
+) -> Result<ResolvedInclude, String> {
+    let beside = Path::new(requesting)
+        .parent()
+        .map(|parent| parent.join(requested));
+    let path = match (include_type, beside) {
+        (IncludeType::Relative, Some(beside)) if beside.is_file() => beside,
+        _ => directory.join(requested),
+    };
+    let content = fs::read_to_string(&path)
+        .map_err(|err| format!("Failed to include {} : {err}", path.display()))?;
+    Ok(ResolvedInclude {
+        resolved_name: path.to_string_lossy().into_owned(),
+        content,
+    })
+}
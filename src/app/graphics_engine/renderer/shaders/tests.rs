@@ -0,0 +1,141 @@
+use std::{fs, path::Path};
+
+use shaderc::IncludeType;
+
+use crate::app::temp_dir::TempDir;
+
+use super::{
+    shader_directory, shader_files,
+    source::{find_source, read_spirv, resolve_include, ShaderSource},
+    ShaderError,
+};
+
+// A SPIR-V header (magic number, version 1.0, generator, ids "bound", schema), then "body"
+fn spirv(bound: u32, body: &[u32]) -> Vec<u8> {
+    [0x0723_0203u32, 0x0001_0000, 0, bound, 0]
+        .iter()
+        .chain(body)
+        .flat_map(|word| word.to_le_bytes())
+        .collect()
+}
+
+// OpCapability Shader : two words
+const CAPABILITY_SHADER: [u32; 2] = [(2 << 16) | 17, 1];
+
+fn spirv_header() -> Vec<u8> {
+    spirv(1, &[])
+}
+
+#[test]
+fn glsl_is_preferred_to_spirv() {
+    let temp = TempDir::new("shaders_preferred");
+    let directory = temp.path();
+    fs::write(directory.join("mesh.vert.spv"), spirv_header()).unwrap();
+    assert_eq!(
+        find_source(directory, "mesh.vert").unwrap(),
+        ShaderSource::Spirv(directory.join("mesh.vert.spv"))
+    );
+    fs::write(directory.join("mesh.vert"), "#version 450").unwrap();
+    assert_eq!(
+        find_source(directory, "mesh.vert").unwrap(),
+        ShaderSource::Glsl(directory.join("mesh.vert"))
+    );
+    assert!(matches!(
+        find_source(directory, "mesh.frag"),
+        Err(ShaderError::Missing(path)) if path == directory.join("mesh.frag")
+    ));
+}
+
+#[test]
+fn spirv_is_checked() {
+    let temp = TempDir::new("shaders_spirv");
+    let directory = temp.path();
+    let valid = directory.join("valid.spv");
+    fs::write(&valid, spirv_header()).unwrap();
+    assert_eq!(read_spirv(&valid).unwrap()[0], 0x0723_0203);
+
+    let instructions = directory.join("instructions.spv");
+    fs::write(&instructions, spirv(1, &CAPABILITY_SHADER)).unwrap();
+    assert_eq!(read_spirv(&instructions).unwrap().len(), 7);
+
+    let invalid = directory.join("invalid.spv");
+    let is_invalid = |content: Vec<u8>| {
+        fs::write(&invalid, content).unwrap();
+        matches!(read_spirv(&invalid), Err(ShaderError::Io(path, _)) if path == invalid)
+    };
+    // GLSL mistaken for SPIR-V
+    assert!(is_invalid(b"#version 450\n\n\n".to_vec()));
+    assert!(is_invalid(spirv(0, &CAPABILITY_SHADER)));
+    // Truncated : the instruction is missing its operand
+    assert!(is_invalid(spirv(1, &CAPABILITY_SHADER[..1])));
+    assert!(is_invalid(spirv(1, &[0, 1])));
+}
+
+#[test]
+fn includes_are_found_beside_then_in_the_directory() {
+    let temp = TempDir::new("shaders_includes");
+    let directory = temp.path();
+    let nested = directory.join("nested");
+    fs::create_dir_all(&nested).unwrap();
+    fs::write(directory.join("common.glsl"), "// common").unwrap();
+    fs::write(nested.join("local.glsl"), "// local").unwrap();
+    let requesting = nested.join("mesh.frag");
+    let requesting = requesting.to_str().unwrap();
+
+    let resolve =
+        |requested, include_type| resolve_include(directory, requested, include_type, requesting);
+    let local = resolve("local.glsl", IncludeType::Relative).unwrap();
+    assert_eq!(Path::new(&local.resolved_name), nested.join("local.glsl"));
+    assert_eq!(local.content, "// local");
+    let common = resolve("common.glsl", IncludeType::Relative).unwrap();
+    assert_eq!(
+        Path::new(&common.resolved_name),
+        directory.join("common.glsl")
+    );
+    let standard = resolve("common.glsl", IncludeType::Standard).unwrap();
+    assert_eq!(standard.content, "// common");
+    // <...> only looks in the directory
+    assert!(resolve("local.glsl", IncludeType::Standard).is_err());
+}
+
+// Includes in subdirectories are watched too
+#[test]
+fn shader_files_are_listed_recursively() {
+    let temp = TempDir::new("shaders_files");
+    let directory = temp.path();
+    fs::create_dir_all(directory.join("lib")).unwrap();
+    fs::write(directory.join("mesh.vert"), "").unwrap();
+    fs::write(directory.join("lib/light.glsl"), "").unwrap();
+    let mut files = shader_files(directory);
+    files.sort();
+    assert_eq!(
+        files,
+        [
+            directory.join("lib/light.glsl"),
+            directory.join("mesh.vert")
+        ]
+    );
+    assert!(shader_files(&directory.join("missing")).is_empty());
+}
+
+// The renderer's shaders, and what they include, are where it reads them
+#[test]
+fn mesh_shaders_are_found() {
+    let directory = shader_directory();
+    for name in ["mesh.vert", "mesh.frag"] {
+        let ShaderSource::Glsl(path) = find_source(&directory, name).unwrap() else {
+            panic!("{name} isn't GLSL");
+        };
+        let text = fs::read_to_string(&path).unwrap();
+        for line in text.lines().filter(|line| line.starts_with("#include")) {
+            let requested = line.trim_start_matches("#include").trim().trim_matches('"');
+            resolve_include(
+                &directory,
+                requested,
+                IncludeType::Relative,
+                path.to_str().unwrap(),
+            )
+            .unwrap();
+        }
+    }
+}
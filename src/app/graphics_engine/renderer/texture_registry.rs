@@ -0,0 +1,164 @@
+use std::collections::{HashMap, HashSet};
+
+use ash::vk::{DescriptorPool, DescriptorSet, DescriptorSetLayout, ImageView, Sampler};
+
+use crate::app::graphics_engine::{
+    device::Image,
+    mesher::{Material, Texture, TextureKey},
+    Device,
+};
+
+use super::{commands::TextureUpload, descriptors, resources};
+
+// Descriptor sets of each pool (another pool is created when they are all used)
+const TEXTURES_PER_POOL: u32 = 64;
+
+// Textures uploaded once to device images shared by every frame, found back by their key with the
+// descriptor set binding them (and the shared sampler) for the fragment shader.
+// A white texel stands for materials without texture.
+// Invalidated textures are destroyed once no frame in flight may sample them, their sets reused
+pub struct TextureRegistry {
+    sampler: Sampler,
+    pools: Vec<DescriptorPool>,
+    sets_left: u32,
+    // Sets of destroyed textures, bound again by the next registrations
+    free_sets: Vec<DescriptorSet>,
+    textures: HashMap<TextureKey, RegisteredTexture>,
+    // Invalidated textures, with the number of frames to start before they can be destroyed
+    retired: Vec<(RegisteredTexture, usize)>,
+    frames_in_flight: usize,
+    white: Texture,
+}
+
+struct RegisteredTexture {
+    image: Image,
+    view: ImageView,
+    set: DescriptorSet,
+}
+
+impl TextureRegistry {
+    pub fn new(device: &Device, frames_in_flight: usize) -> TextureRegistry {
+        TextureRegistry {
+            sampler: resources::create_sampler(device),
+            pools: Vec::new(),
+            sets_left: 0,
+            free_sets: Vec::new(),
+            textures: HashMap::new(),
+            retired: Vec::new(),
+            frames_in_flight,
+            white: Texture::new(1, 1, vec![u8::MAX; 4]),
+        }
+    }
+
+    // Descriptor sets are freed with their pools. The device must be idle
+    pub fn destroy(&mut self, device: &Device) {
+        for (_, mut texture) in self.textures.drain() {
+            destroy_texture(device, &mut texture);
+        }
+        for (mut texture, _) in self.retired.drain(..) {
+            destroy_texture(device, &mut texture);
+        }
+        unsafe {
+            for pool in &self.pools {
+                device.destroy_descriptor_pool(*pool, None);
+            }
+            device.destroy_sampler(self.sampler, None);
+        }
+    }
+
+    // Textures of "materials" (white for those without) that aren't registered yet, once each
+    pub fn missing<'a>(&self, materials: impl Iterator<Item = &'a Material>) -> Vec<Texture> {
+        let mut seen = HashSet::new();
+        materials
+            .map(|material| material.texture.as_ref().unwrap_or(&self.white))
+            .filter(|texture| !self.textures.contains_key(&texture.key) && seen.insert(texture.key))
+            .cloned()
+            .collect()
+    }
+
+    // The texture of "key" won't be sampled again (frames in flight may still sample it)
+    pub fn invalidate(&mut self, key: &TextureKey) {
+        if let Some(texture) = self.textures.remove(key) {
+            self.retired.push((texture, self.frames_in_flight));
+        }
+    }
+
+    // A frame was started : its last rendering is done. Frames are started in turn, so retired
+    // textures no frame samples anymore after "frames_in_flight" starts are destroyed
+    pub fn frame_started(&mut self, device: &Device) {
+        for (_, frames_left) in &mut self.retired {
+            *frames_left -= 1;
+        }
+        for (mut texture, _) in self
+            .retired
+            .extract_if(.., |(_, frames_left)| *frames_left == 0)
+        {
+            destroy_texture(device, &mut texture);
+            self.free_sets.push(texture.set);
+        }
+    }
+
+    // Set binding the texture of "material", which must be registered
+    pub fn set(&self, material: &Material) -> DescriptorSet {
+        let texture = material.texture.as_ref().unwrap_or(&self.white);
+        self.textures[&texture.key].set
+    }
+
+    // Register "textures" : images and sets are created, their pixels staged.
+    // The uploads must be recorded before any draw sampling them, their staging buffers kept
+    // until then
+    pub fn register(
+        &mut self,
+        device: &Device,
+        layout: DescriptorSetLayout,
+        textures: &[Texture],
+    ) -> Vec<TextureUpload> {
+        let mut uploads = Vec::with_capacity(textures.len());
+        for texture in textures {
+            let image = resources::allocate_texture(device, texture.width, texture.height);
+            let view = resources::create_texture_view(device, &image);
+            let set = self.allocate_set(device, layout, view);
+
+            let staging = resources::allocate_staging_pixels(device, texture.pixels.len() as u64);
+            unsafe {
+                staging
+                    .ptr
+                    .copy_from(texture.pixels.as_ptr(), texture.pixels.len())
+            };
+            uploads.push(TextureUpload {
+                staging,
+                image: *image,
+                width: texture.width,
+                height: texture.height,
+            });
+            self.textures
+                .insert(texture.key, RegisteredTexture { image, view, set });
+        }
+        uploads
+    }
+
+    fn allocate_set(
+        &mut self,
+        device: &Device,
+        layout: DescriptorSetLayout,
+        view: ImageView,
+    ) -> DescriptorSet {
+        if let Some(set) = self.free_sets.pop() {
+            descriptors::configure_texture_set(device, set, view, self.sampler);
+            return set;
+        }
+        if self.sets_left == 0 {
+            self.pools
+                .push(descriptors::create_texture_pool(device, TEXTURES_PER_POOL));
+            self.sets_left = TEXTURES_PER_POOL;
+        }
+        self.sets_left -= 1;
+        let pool = self.pools.last().unwrap();
+        descriptors::allocate_configure_texture_set(device, pool, &[layout], view, self.sampler)
+    }
+}
+
+fn destroy_texture(device: &Device, texture: &mut RegisteredTexture) {
+    unsafe { device.destroy_image_view(texture.view, None) };
+    device.ct_destroy_image(&mut texture.image);
+}
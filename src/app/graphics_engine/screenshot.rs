@@ -0,0 +1,72 @@
+use std::{fmt, fs::File, io::BufWriter, path::Path};
+
+use ash::vk::{Extent2D, Format};
+
+#[derive(Debug)]
+pub enum ScreenshotError {
+    // The swapchain images can't be copied from (no TRANSFER_SRC usage)
+    NotCapturable,
+    // Pixels of this format can't be converted to RGBA8
+    UnsupportedFormat(Format),
+    Png(png::EncodingError),
+}
+
+impl fmt::Display for ScreenshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScreenshotError::NotCapturable => write!(f, "swapchain images can't be copied"),
+            ScreenshotError::UnsupportedFormat(format) => {
+                write!(f, "unsupported image format {format:?}")
+            }
+            ScreenshotError::Png(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for ScreenshotError {}
+
+impl From<png::EncodingError> for ScreenshotError {
+    fn from(err: png::EncodingError) -> ScreenshotError {
+        ScreenshotError::Png(err)
+    }
+}
+
+// Bytes per pixel of images of "format" read back, if they can be converted to RGBA8
+// (checked before reading anything back)
+pub fn pixel_size(format: Format) -> Result<u64, ScreenshotError> {
+    match format {
+        Format::R8G8B8A8_SRGB
+        | Format::R8G8B8A8_UNORM
+        | Format::B8G8R8A8_SRGB
+        | Format::B8G8R8A8_UNORM => Ok(4),
+        _ => Err(ScreenshotError::UnsupportedFormat(format)),
+    }
+}
+
+// Pixels read back from an image of "format", reordered as RGBA8.
+// Bytes are kept as they are : sRGB formats stay sRGB encoded, as PNGs expect
+pub fn to_rgba8(format: Format, mut pixels: Vec<u8>) -> Result<Vec<u8>, ScreenshotError> {
+    pixel_size(format)?;
+    if matches!(format, Format::B8G8R8A8_SRGB | Format::B8G8R8A8_UNORM) {
+        for pixel in pixels.chunks_exact_mut(4) {
+            pixel.swap(0, 2);
+        }
+    }
+    // Presented images are opaque, whatever alpha was written
+    for pixel in pixels.chunks_exact_mut(4) {
+        pixel[3] = u8::MAX;
+    }
+    Ok(pixels)
+}
+
+// "rgba" : RGBA8 rows from top to bottom covering "extent"
+pub fn save_png(path: &Path, extent: Extent2D, rgba: &[u8]) -> Result<(), ScreenshotError> {
+    let file = File::create(path).map_err(png::EncodingError::from)?;
+    let mut encoder = png::Encoder::new(BufWriter::new(file), extent.width, extent.height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(rgba)?;
+    writer.finish()?;
+    Ok(())
+}
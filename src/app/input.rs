@@ -0,0 +1,152 @@
+mod bindings;
+#[cfg(test)]
+mod tests;
+
+use std::collections::HashSet;
+
+pub use bindings::{Action, Binding, Bindings};
+use glam::Vec2;
+use winit::{
+    event::{DeviceEvent, ElementState, MouseScrollDelta, WindowEvent},
+    keyboard::PhysicalKey,
+};
+
+// Pixels scrolled by touchpads for one wheel line
+const PIXELS_PER_LINE: f32 = 20.;
+
+// Keyboard, mouse and scroll state built from winit events, read by the model each step.
+// "Just" pressed and motions are what happened since the previous step.
+pub struct Input {
+    pub bindings: Bindings,
+    // Keys and buttons
+    held: HashSet<Binding>,
+    just_pressed: HashSet<Binding>,
+    // Mouse
+    mouse_delta: Vec2,
+    scroll_delta: Vec2,
+    cursor_grabbed: bool,
+}
+
+impl Input {
+    pub fn new(bindings: Bindings) -> Input {
+        Input {
+            bindings,
+            held: HashSet::new(),
+            just_pressed: HashSet::new(),
+            mouse_delta: Vec2::ZERO,
+            scroll_delta: Vec2::ZERO,
+            cursor_grabbed: false,
+        }
+    }
+
+    // Events of the window (keys, buttons and wheel)
+    pub fn handle_window_event(&mut self, event: &WindowEvent) {
+        match event {
+            WindowEvent::KeyboardInput { event, .. } => {
+                // Repeats aren't new presses
+                if event.repeat {
+                    return;
+                }
+                if let PhysicalKey::Code(code) = event.physical_key {
+                    self.set(Binding::Key(code), event.state);
+                }
+            }
+            WindowEvent::MouseInput { state, button, .. } => {
+                self.set(Binding::Mouse(*button), *state);
+            }
+            WindowEvent::MouseWheel { delta, .. } => {
+                self.scroll_delta += match delta {
+                    MouseScrollDelta::LineDelta(x, y) => Vec2::new(*x, *y),
+                    MouseScrollDelta::PixelDelta(position) => {
+                        Vec2::new(position.x as f32, position.y as f32) / PIXELS_PER_LINE
+                    }
+                };
+            }
+            // Releases happening elsewhere are never received : nothing stays held
+            WindowEvent::Focused(false) => self.release_all(),
+            _ => {}
+        }
+    }
+
+    // Raw mouse motion, still received when the cursor is grabbed
+    pub fn handle_device_event(&mut self, event: &DeviceEvent) {
+        if let DeviceEvent::MouseMotion { delta } = event {
+            self.mouse_delta += Vec2::new(delta.0 as f32, delta.1 as f32);
+        }
+    }
+
+    // The model stepped : start gathering the next step's changes
+    pub fn end_step(&mut self) {
+        self.just_pressed.clear();
+        self.mouse_delta = Vec2::ZERO;
+        self.scroll_delta = Vec2::ZERO;
+    }
+
+    pub fn press(&mut self, binding: Binding) {
+        if self.held.insert(binding) {
+            self.just_pressed.insert(binding);
+        }
+    }
+
+    pub fn release(&mut self, binding: Binding) {
+        self.held.remove(&binding);
+    }
+
+    fn set(&mut self, binding: Binding, state: ElementState) {
+        match state {
+            ElementState::Pressed => self.press(binding),
+            ElementState::Released => self.release(binding),
+        }
+    }
+
+    fn release_all(&mut self) {
+        let held: Vec<Binding> = self.held.iter().copied().collect();
+        for binding in held {
+            self.release(binding);
+        }
+    }
+}
+
+// Queries for the model
+impl Input {
+    // Keys and buttons
+    pub fn is_held(&self, binding: Binding) -> bool {
+        self.held.contains(&binding)
+    }
+    pub fn is_just_pressed(&self, binding: Binding) -> bool {
+        self.just_pressed.contains(&binding)
+    }
+
+    // Actions (through their bindings)
+    pub fn is_action_held(&self, action: Action) -> bool {
+        self.any_binding(action, |binding| self.is_held(binding))
+    }
+    pub fn is_action_just_pressed(&self, action: Action) -> bool {
+        self.any_binding(action, |binding| self.is_just_pressed(binding))
+    }
+
+    fn any_binding(&self, action: Action, check: impl Fn(Binding) -> bool) -> bool {
+        self.bindings
+            .bindings_of(action)
+            .iter()
+            .any(|binding| check(*binding))
+    }
+
+    // Mouse
+    // Raw motion since the previous step (not bound to the window, nor accelerated)
+    pub fn mouse_delta(&self) -> Vec2 {
+        self.mouse_delta
+    }
+    // Wheel lines since the previous step (positive y : away from the user)
+    pub fn scroll_delta(&self) -> Vec2 {
+        self.scroll_delta
+    }
+
+    pub fn is_cursor_grabbed(&self) -> bool {
+        self.cursor_grabbed
+    }
+    // Only records it : the window applies it
+    pub fn set_cursor_grabbed(&mut self, grabbed: bool) {
+        self.cursor_grabbed = grabbed;
+    }
+}
@@ -0,0 +1,83 @@
+use std::collections::HashMap;
+
+use winit::{event::MouseButton, keyboard::KeyCode};
+
+// What the user wants to do, whatever key or button does it
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Action {
+    MoveForward,
+    MoveBackward,
+    MoveLeft,
+    MoveRight,
+    MoveUp,
+    MoveDown,
+    // Speed modifiers
+    Fast,
+    Slow,
+    // Mouse drags
+    Rotate,
+    Pan,
+    SwitchCamera,
+    SwitchProjection,
+    ToggleAnimation,
+    ToggleCursorGrab,
+    Screenshot,
+    SaveScene,
+}
+
+// A key (by its position on the keyboard, whatever the layout) or a mouse button
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Binding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+}
+
+// Bindings triggering each action (any of them does)
+pub struct Bindings {
+    actions: HashMap<Action, Vec<Binding>>,
+}
+
+impl Bindings {
+    pub fn empty() -> Bindings {
+        Bindings {
+            actions: HashMap::new(),
+        }
+    }
+
+    // WASD to move, space and left shift to go up and down, left control / alt to go faster / slower,
+    // left drag to rotate, right (or middle) drag to pan, C to switch camera controller,
+    // O to switch projection (perspective, infinite, orthographic),
+    // P to pause the animation, escape to grab the cursor, F12 for screenshots, F5 to save the scene
+    pub fn default_bindings() -> Bindings {
+        let mut bindings = Bindings::empty();
+        bindings.bind(Action::MoveForward, Binding::Key(KeyCode::KeyW));
+        bindings.bind(Action::MoveBackward, Binding::Key(KeyCode::KeyS));
+        bindings.bind(Action::MoveLeft, Binding::Key(KeyCode::KeyA));
+        bindings.bind(Action::MoveRight, Binding::Key(KeyCode::KeyD));
+        bindings.bind(Action::MoveUp, Binding::Key(KeyCode::Space));
+        bindings.bind(Action::MoveDown, Binding::Key(KeyCode::ShiftLeft));
+        bindings.bind(Action::Fast, Binding::Key(KeyCode::ControlLeft));
+        bindings.bind(Action::Slow, Binding::Key(KeyCode::AltLeft));
+        bindings.bind(Action::Rotate, Binding::Mouse(MouseButton::Left));
+        bindings.bind(Action::Pan, Binding::Mouse(MouseButton::Right));
+        bindings.bind(Action::Pan, Binding::Mouse(MouseButton::Middle));
+        bindings.bind(Action::SwitchCamera, Binding::Key(KeyCode::KeyC));
+        bindings.bind(Action::SwitchProjection, Binding::Key(KeyCode::KeyO));
+        bindings.bind(Action::ToggleAnimation, Binding::Key(KeyCode::KeyP));
+        bindings.bind(Action::ToggleCursorGrab, Binding::Key(KeyCode::Escape));
+        bindings.bind(Action::Screenshot, Binding::Key(KeyCode::F12));
+        bindings.bind(Action::SaveScene, Binding::Key(KeyCode::F5));
+        bindings
+    }
+
+    pub fn bind(&mut self, action: Action, binding: Binding) {
+        let bindings = self.actions.entry(action).or_default();
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+    }
+
+    pub fn bindings_of(&self, action: Action) -> &[Binding] {
+        self.actions.get(&action).map_or(&[], Vec::as_slice)
+    }
+}
@@ -0,0 +1,52 @@
+use winit::{event::MouseButton, keyboard::KeyCode};
+
+use super::{Action, Binding, Bindings, Input};
+
+const W: Binding = Binding::Key(KeyCode::KeyW);
+const UP: Binding = Binding::Key(KeyCode::ArrowUp);
+const LEFT_CLICK: Binding = Binding::Mouse(MouseButton::Left);
+
+#[test]
+fn press_and_release_across_steps() {
+    let mut input = Input::new(Bindings::empty());
+    input.press(W);
+    assert!(input.is_held(W) && input.is_just_pressed(W));
+
+    // Still held the next step, but not new anymore
+    input.end_step();
+    input.press(W); // repeat
+    assert!(input.is_held(W) && !input.is_just_pressed(W));
+
+    input.release(W);
+    assert!(!input.is_held(W));
+    input.end_step();
+    input.press(W);
+    assert!(input.is_just_pressed(W));
+}
+
+// A tap shorter than a step is still seen by the step
+#[test]
+fn tap_within_a_step() {
+    let mut input = Input::new(Bindings::empty());
+    input.press(LEFT_CLICK);
+    input.release(LEFT_CLICK);
+    assert!(!input.is_held(LEFT_CLICK));
+    assert!(input.is_just_pressed(LEFT_CLICK));
+}
+
+#[test]
+fn actions_follow_bindings() {
+    let mut input = Input::new(Bindings::default_bindings());
+    input.press(UP);
+    assert!(!input.is_action_held(Action::MoveForward));
+
+    input.bindings.bind(Action::MoveForward, UP);
+    assert!(input.is_action_held(Action::MoveForward));
+    assert!(input.is_action_just_pressed(Action::MoveForward));
+
+    // Default binding still there
+    input.release(UP);
+    assert!(!input.is_action_held(Action::MoveForward));
+    input.press(W);
+    assert!(input.is_action_held(Action::MoveForward));
+}
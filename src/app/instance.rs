@@ -1,4 +1,5 @@
 use ash::{
+    prelude::VkResult,
     vk::{self, ApplicationInfo, ExtensionProperties, InstanceCreateInfo, LayerProperties},
     Entry,
 };
@@ -12,13 +13,14 @@ const VALIDATION_LAYER: *const c_char = c"VK_LAYER_KHRONOS_validation".as_ptr();
 
 // Custom instance for presenting :
 // - Appropriate extensions for creating surfaces on the given display => surfaceKHR extension + OS-specific window extension
+//   (none when headless : no display, no surface)
 // - Hold entry => Must be the only instance
-// - Validation layers on Debug
+// - Validation layers on Debug (when installed)
 pub struct Instance {
     entry: Entry,
     instance: ash::Instance,
-    // surfaceKHR extension vk fns
-    surface_khr_instance: ash::khr::surface::Instance,
+    // surfaceKHR extension vk fns (None when headless)
+    surface_khr_instance: Option<ash::khr::surface::Instance>,
 }
 
 // Deref : ash::Instance
@@ -40,13 +42,32 @@ impl Instance {
     // "raw_display_handle" arg used to enable display_compatible surfaceKHR extension
     pub fn new(raw_display_handle: RawDisplayHandle) -> Instance {
         let entry: Entry = unsafe { Entry::load().expect("Failed to load vulkan.") };
-        let instance = create_instance(&entry, raw_display_handle);
-        let surface_khr_instance = ash::khr::surface::Instance::new(&entry, &instance);
-        Instance {
+        Instance::with_display(entry, Some(raw_display_handle)).expect("Failed to create instance.")
+    }
+
+    // No surface can be created : for offscreen rendering only
+    pub fn new_headless() -> Instance {
+        Instance::try_new_headless().expect("Failed to create a headless instance.")
+    }
+
+    // None when vulkan isn't installed or has no driver to create an instance
+    pub fn try_new_headless() -> Option<Instance> {
+        let entry: Entry = unsafe { Entry::load() }.ok()?;
+        Instance::with_display(entry, None).ok()
+    }
+
+    fn with_display(
+        entry: Entry,
+        raw_display_handle: Option<RawDisplayHandle>,
+    ) -> VkResult<Instance> {
+        let instance = create_instance(&entry, raw_display_handle)?;
+        let surface_khr_instance =
+            raw_display_handle.map(|_| ash::khr::surface::Instance::new(&entry, &instance));
+        Ok(Instance {
             entry,
             instance,
             surface_khr_instance,
-        }
+        })
     }
 
     pub fn entry(&self) -> &Entry {
@@ -54,38 +75,44 @@ impl Instance {
     }
 
     pub fn surface_khr(&self) -> &ash::khr::surface::Instance {
-        &self.surface_khr_instance
+        self.surface_khr_instance
+            .as_ref()
+            .expect("Headless instance has no surface extension.")
     }
 }
 
-fn create_instance(entry: &Entry, raw_display_handle: RawDisplayHandle) -> ash::Instance {
+fn create_instance(
+    entry: &Entry,
+    raw_display_handle: Option<RawDisplayHandle>,
+) -> VkResult<ash::Instance> {
     // SPECIFY : layers
+    // (validation is skipped when not installed, e.g. on CI machines with only a software driver)
+    let available_layers = unsafe { entry.enumerate_instance_layer_properties() }?;
     let validation_layer = if cfg!(debug_assertions) {
-        vec![VALIDATION_LAYER]
+        if is_layer_available(VALIDATION_LAYER, &available_layers) {
+            vec![VALIDATION_LAYER]
+        } else {
+            eprintln!("Validation layer unavailable, running without it.");
+            Vec::new()
+        }
     } else {
         Vec::new()
     };
     let layers = [validation_layer].concat();
-    // availability check (panic if unavailables)
-    let available_layers = unsafe { entry.enumerate_instance_layer_properties() }
-        .expect("Failed to get available layers.");
-    for layer in &layers {
-        if !is_layer_available(*layer, &available_layers) {
-            panic!("Some layers are unavailable.");
-        }
-    }
 
     // SPECIFY : extensions
-    let surface_extensions = ash_window::enumerate_required_extensions(raw_display_handle)
-        .expect("Failed to get graphics extensions from display.")
-        .to_vec();
+    let surface_extensions = match raw_display_handle {
+        Some(raw_display_handle) => ash_window::enumerate_required_extensions(raw_display_handle)
+            .expect("Failed to get graphics extensions from display.")
+            .to_vec(),
+        None => Vec::new(),
+    };
     let extensions = [surface_extensions].concat();
-    // availability check (panic if unavailables)
-    let available_extensions = unsafe { entry.enumerate_instance_extension_properties(None) }
-        .expect("Failed to get available extensions.");
+    // availability check
+    let available_extensions = unsafe { entry.enumerate_instance_extension_properties(None) }?;
     for extension in &extensions {
         if !is_extension_available(*extension, &available_extensions) {
-            panic!("Some extensions are not supported.")
+            return Err(vk::Result::ERROR_EXTENSION_NOT_PRESENT);
         }
     }
 
@@ -97,14 +124,10 @@ fn create_instance(entry: &Entry, raw_display_handle: RawDisplayHandle) -> ash::
         .enabled_layer_names(&layers)
         .enabled_extension_names(&extensions)
         .application_info(&application_info);
-    unsafe {
-        entry
-            .create_instance(&create_info, None)
-            .expect("Failed to create instance.")
-    }
+    unsafe { entry.create_instance(&create_info, None) }
 }
 
-fn is_layer_available(layer: *const c_char, available_layers: &Vec<LayerProperties>) -> bool {
+fn is_layer_available(layer: *const c_char, available_layers: &[LayerProperties]) -> bool {
     let layer = unsafe { CStr::from_ptr(layer) };
     available_layers
         .iter()
@@ -113,7 +136,7 @@ fn is_layer_available(layer: *const c_char, available_layers: &Vec<LayerProperti
 
 fn is_extension_available(
     extension: *const c_char,
-    available_extensions: &Vec<ExtensionProperties>,
+    available_extensions: &[ExtensionProperties],
 ) -> bool {
     let extension = unsafe { CStr::from_ptr(extension) };
     available_extensions.iter().any(|available_extension| {
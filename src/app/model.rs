@@ -1,62 +1,427 @@
 mod camera;
+mod lighting;
 pub mod object;
+pub mod scene;
+pub mod scene_file;
 pub mod space;
+mod timestep;
+pub mod world;
 
-pub use camera::Camera;
-use glam::{Quat, Vec3};
-use object::{Cube, Octahedron};
-use space::Coord;
-use std::time::Instant;
+pub use camera::{Camera, CameraController};
+use glam::Mat4;
+pub use lighting::{Lighting, MAX_LIGHTS};
+use object::Placed;
+use scene::{NodeId, SceneGraph, Transform};
+use scene_file::{
+    MeshFiles, NodeDescription, ObjectDescription, SceneDescription, SceneError, Shape,
+    SCENE_VERSION,
+};
+use space::{Coord, Orientation};
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    time::Instant,
+};
+use timestep::FixedTimestep;
+use world::{systems, Material, Name, Renderable, System, Velocity, World};
 
-use super::graphics_engine::ToMesh;
+use super::{
+    graphics_engine::{MeshKey, TextureKey, ToMesh},
+    input::{Action, Input},
+};
 
-// Handle vertices based on time
+// Simulation ticks per second, and the most run per update (when frames are slow)
+const TICK_RATE: f64 = 60.;
+const MAX_CATCH_UP: u32 = 8;
+
+// Scene of "Model::new"
+pub(crate) const SHOWCASE: &str = include_str!("../../scenes/showcase.ron");
+
+// Handle vertices based on time :
+// the simulation advances by fixed ticks, the camera follows the user every update,
+// rendering interpolates transforms between the last two ticks
 pub struct Model {
     camera: Camera,
-    // Objects
-    octahedrons: Vec<Octahedron>,
-    cubes: Vec<Cube>,
+    camera_controller: CameraController,
+    lighting: Lighting,
+    // Objects, as entities moved by the systems
+    world: World,
+    systems: Vec<System>,
+    // Objects placed by scene graph nodes, and what to save of those nodes
+    scene: SceneGraph,
+    nodes: Vec<DescribedNode>,
     // Stepping
-    last_step: Instant,
+    animating: bool,
+    timestep: FixedTimestep,
+    // Transforms before the last tick, in "objects_to_draw" order
+    previous_transforms: Vec<Mat4>,
+    last_update: Instant,
 }
 
 impl Model {
+    // The showcase scene, with a planet spinning with its moon around it above
     pub fn new() -> Model {
-        let camera = Camera::new(2. * Vec3::ONE, Vec3::ZERO);
-        let octahedron_1 = Octahedron::new_unoriented(Coord::new(0.5, 0.5, 0.5), 0.5);
-        let cube_1 = Cube::new_unoriented(Coord::new(-0.5, -0.5, -0.5), 0.25);
-        Model {
+        let showcase = SceneDescription::parse(SHOWCASE).expect("Failed to parse the showcase.");
+        Model::from_scene(&showcase, Path::new(""), &mut MeshFiles::new())
+            .expect("Failed to build the showcase.")
+    }
+
+    // "directory" : where the paths of Mesh shapes are relative to,
+    // "files" : mesh files loaded already (by a previous model)
+    pub fn from_scene(
+        scene: &SceneDescription,
+        directory: &Path,
+        files: &mut MeshFiles,
+    ) -> Result<Model, SceneError> {
+        scene.validate()?;
+        let mut world = World::new();
+        for object in &scene.objects {
+            spawn_object(&mut world, object, directory, files)?;
+        }
+        let mut scene_graph = SceneGraph::new();
+        let mut nodes = Vec::with_capacity(scene.nodes.len());
+        for node in &scene.nodes {
+            let node = add_node(&mut scene_graph, &nodes, node, directory, files)?;
+            nodes.push(node);
+        }
+        scene_graph.update_world_transforms();
+        // Materials are given by a system : draw them from the first frame
+        systems::apply_materials(&mut world, 0.);
+
+        let camera = scene.camera.clone();
+        let camera_controller = CameraController::orbit(&camera);
+        Ok(Model {
             camera,
-            octahedrons: vec![octahedron_1],
-            cubes: vec![cube_1],
-            last_step: Instant::now(),
+            camera_controller,
+            lighting: scene.lighting.clone(),
+            world,
+            systems: systems::DEFAULT_SYSTEMS.to_vec(),
+            scene: scene_graph,
+            nodes,
+            animating: true,
+            timestep: FixedTimestep::new(TICK_RATE, MAX_CATCH_UP),
+            previous_transforms: Vec::new(),
+            last_update: Instant::now(),
+        })
+    }
+
+    // A scene file, see SceneDescription
+    pub fn load_scene(path: &Path, files: &mut MeshFiles) -> Result<Model, SceneError> {
+        let scene = SceneDescription::read(path)?;
+        let directory = path.parent().unwrap_or(Path::new(""));
+        Model::from_scene(&scene, directory, files)
+    }
+
+    // The camera as it is now, the objects and nodes as of the last tick (only entities built
+    // from a description). "directory" : where Mesh paths will be relative to
+    pub fn to_scene(&self, directory: &Path) -> SceneDescription {
+        let objects = self
+            .world
+            .query2::<Shape, Transform>()
+            .map(|(entity, shape, transform)| ObjectDescription {
+                name: self
+                    .world
+                    .get::<Name>(entity)
+                    .map_or_else(String::new, |name| name.0.clone()),
+                shape: relative_shape(shape, directory),
+                position: Coord {
+                    coord: transform.translation,
+                },
+                orientation: transform.rotation,
+                size: transform.scale.x,
+                material: self.world.get::<Material>(entity).copied(),
+                velocity: self.world.get::<Velocity>(entity).copied(),
+            })
+            .collect();
+        let nodes = self
+            .nodes
+            .iter()
+            .map(|node| {
+                let local = self.scene.local(node.id);
+                let parent = self.scene.parent(node.id).map(|parent| {
+                    self.nodes
+                        .iter()
+                        .position(|node| node.id == parent)
+                        .expect("Failed to find a node's parent.")
+                });
+                NodeDescription {
+                    name: node.name.clone(),
+                    parent,
+                    position: Coord {
+                        coord: local.translation,
+                    },
+                    orientation: local.rotation,
+                    size: local.scale.x,
+                    velocity: self.scene.velocity(node.id),
+                    objects: node
+                        .objects
+                        .iter()
+                        .map(|object| ObjectDescription {
+                            shape: relative_shape(&object.shape, directory),
+                            ..object.clone()
+                        })
+                        .collect(),
+                }
+            })
+            .collect();
+        SceneDescription {
+            version: SCENE_VERSION,
+            camera: self.camera.clone(),
+            lighting: self.lighting.clone(),
+            objects,
+            nodes,
         }
     }
 
-    pub fn step_if_enough_time(&mut self) {
-        let elapsed = self.last_step.elapsed().as_millis();
-        if elapsed >= 20 {
-            self.octahedrons[0]
-                .orientation
-                .rotate(Quat::from_rotation_x(0.1));
-            self.cubes[0].orientation.rotate(Quat::from_rotation_x(0.1));
-            self.last_step = Instant::now();
+    // Replacing "previous" (reloaded) : keep its camera, controller and pause
+    pub fn keep_view_of(&mut self, previous: Model) {
+        self.camera = previous.camera;
+        self.camera_controller = previous.camera_controller;
+        self.animating = previous.animating;
+    }
+
+    pub fn save_scene(&self, path: &Path) -> Result<(), SceneError> {
+        let directory = path.parent().unwrap_or(Path::new(""));
+        self.to_scene(directory).write(path)
+    }
+
+    // OBJ meshes (at the origin) and glTF scenes (.gltf, .glb) to add to the model,
+    // unreadable files are reported and skipped
+    pub fn load_files(&mut self, paths: &[impl AsRef<Path>], files: &mut MeshFiles) {
+        for path in paths {
+            // Absolute : saved scenes find it from anywhere
+            let path = absolute(path.as_ref());
+            let count = match files.load(&path) {
+                Ok(objects) => objects.len(),
+                Err(err) => {
+                    eprintln!("Failed to load {err}");
+                    continue;
+                }
+            };
+            let name = path
+                .file_name()
+                .map_or_else(String::new, |name| name.to_string_lossy().into_owned());
+            for index in 0..count {
+                let object = ObjectDescription {
+                    name: name.clone(),
+                    shape: Shape::Mesh {
+                        path: path.clone(),
+                        index,
+                    },
+                    position: Coord::new(0., 0., 0.),
+                    orientation: Orientation::initial(),
+                    size: 1.,
+                    material: None,
+                    velocity: None,
+                };
+                spawn_object(&mut self.world, &object, Path::new(""), files)
+                    .expect("Failed to spawn a loaded object.");
+            }
         }
     }
 
-    pub fn objects_to_draw(&self) -> Vec<&dyn ToMesh> {
-        let mut objects: Vec<&dyn ToMesh> =
-            Vec::with_capacity(self.cubes.len() + self.octahedrons.len());
-        for octahedron in &self.octahedrons {
-            objects.push(octahedron);
+    // "input" : what the user did since the previous update
+    pub fn update(&mut self, input: &Input) {
+        let now = Instant::now();
+        let elapsed = (now - self.last_update).as_secs_f32();
+        self.last_update = now;
+
+        // Camera
+        if input.is_action_just_pressed(Action::SwitchCamera) {
+            self.camera_controller = self.camera_controller.next(&self.camera);
+        }
+        if input.is_action_just_pressed(Action::SwitchProjection) {
+            self.camera.switch_projection();
+        }
+        self.camera_controller
+            .update(&mut self.camera, input, elapsed);
+
+        // Simulation
+        if input.is_action_just_pressed(Action::ToggleAnimation) {
+            self.animating = !self.animating;
         }
-        for cube in &self.cubes {
-            objects.push(cube);
+        for _ in 0..self.timestep.advance() {
+            self.previous_transforms = self.transforms();
+            self.tick(self.timestep.dt());
+        }
+    }
+
+    // Advance the simulation by "dt" seconds
+    fn tick(&mut self, dt: f32) {
+        // Paused : time stands still, but systems still run (materials still apply)
+        let dt = if self.animating { dt } else { 0. };
+        for system in &self.systems {
+            system(&mut self.world, dt);
         }
+        self.scene.tick(dt);
+        self.scene.update_world_transforms();
+    }
+
+    fn transforms(&self) -> Vec<Mat4> {
+        self.objects_to_draw()
+            .iter()
+            .map(|placed| placed.transform)
+            .collect()
+    }
+
+    // Objects between the last two ticks, as far as the time since the last tick goes
+    // (objects added since are drawn as they are)
+    pub fn interpolated_objects(&self) -> Vec<Placed<'_>> {
+        let alpha = self.timestep.alpha();
+        let objects = self.objects_to_draw();
+        let interpolating = self.previous_transforms.len() == objects.len();
         objects
+            .into_iter()
+            .enumerate()
+            .map(|(idx, placed)| {
+                let transform = if interpolating {
+                    timestep::interpolate(self.previous_transforms[idx], placed.transform, alpha)
+                } else {
+                    placed.transform
+                };
+                Placed {
+                    transform,
+                    ..placed
+                }
+            })
+            .collect()
+    }
+
+    // As of the last tick
+    pub fn objects_to_draw(&self) -> Vec<Placed<'_>> {
+        systems::renderables(&self.world)
+            .chain(self.scene.drawables())
+            .collect()
+    }
+
+    // Geometries drawn (the others needn't stay on the device)
+    pub fn mesh_keys(&self) -> HashSet<MeshKey> {
+        self.objects_to_draw()
+            .iter()
+            .map(|placed| placed.mesh_key())
+            .collect()
+    }
+
+    // Textures drawn (the others needn't stay on the device)
+    pub fn texture_keys(&self) -> HashSet<TextureKey> {
+        self.objects_to_draw()
+            .iter()
+            .filter_map(|placed| Some(placed.material().texture?.key))
+            .collect()
     }
 
     pub fn camera(&self) -> &Camera {
         &self.camera
     }
+
+    pub fn camera_controller(&self) -> &CameraController {
+        &self.camera_controller
+    }
+
+    pub fn lighting(&self) -> &Lighting {
+        &self.lighting
+    }
+}
+
+// A node built from a description : its name and its objects (Mesh paths as found from the
+// working directory), saved again with the node's transform and velocity of the moment
+struct DescribedNode {
+    id: NodeId,
+    name: String,
+    objects: Vec<ObjectDescription>,
+}
+
+// An entity for "object" : its name, transform, renderable and shape, and its material and
+// velocity if it has them. "directory" : where its Mesh path is relative to
+fn spawn_object(
+    world: &mut World,
+    object: &ObjectDescription,
+    directory: &Path,
+    files: &mut MeshFiles,
+) -> Result<(), SceneError> {
+    let renderable = Renderable::new(object.shape.build(directory, files)?);
+    let entity = world.spawn();
+    world.insert(entity, Name(object.name.clone()));
+    world.insert(
+        entity,
+        Transform::new(object.position.to_vec3(), object.orientation, object.size),
+    );
+    world.insert(entity, renderable);
+    world.insert(entity, found_shape(&object.shape, directory));
+    if let Some(material) = object.material {
+        world.insert(entity, material);
+    }
+    if let Some(velocity) = object.velocity {
+        world.insert(entity, velocity);
+    }
+    Ok(())
+}
+
+// A node for "node" in "scene", under the node of its parent ("added" : the nodes listed before
+// it), with its objects (each placed by a child node). "directory" : where Mesh paths are
+// relative to
+fn add_node(
+    scene: &mut SceneGraph,
+    added: &[DescribedNode],
+    node: &NodeDescription,
+    directory: &Path,
+    files: &mut MeshFiles,
+) -> Result<DescribedNode, SceneError> {
+    let parent = node.parent.map(|parent| added[parent].id);
+    let local = Transform::new(node.position.to_vec3(), node.orientation, node.size);
+    let id = scene.add_node(parent, local);
+    if let Some(velocity) = node.velocity {
+        scene.set_velocity(id, velocity);
+    }
+    let mut objects = Vec::with_capacity(node.objects.len());
+    for object in &node.objects {
+        let mut renderable = Renderable::new(object.shape.build(directory, files)?);
+        renderable.tint = object.material;
+        let placement = Transform::new(object.position.to_vec3(), object.orientation, object.size);
+        let placement = scene.add_node(Some(id), placement);
+        scene.attach(placement, Box::new(renderable));
+        objects.push(ObjectDescription {
+            shape: found_shape(&object.shape, directory),
+            ..object.clone()
+        });
+    }
+    Ok(DescribedNode {
+        id,
+        name: node.name.clone(),
+        objects,
+    })
+}
+
+// Mesh paths relative to "directory" as found from the working directory
+fn found_shape(shape: &Shape, directory: &Path) -> Shape {
+    match shape {
+        Shape::Mesh { path, index } => Shape::Mesh {
+            path: directory.join(path),
+            index: *index,
+        },
+        shape => shape.clone(),
+    }
+}
+
+// Mesh paths found from the working directory made relative to "directory" (when inside)
+fn relative_shape(shape: &Shape, directory: &Path) -> Shape {
+    match shape {
+        Shape::Mesh { path, index } => Shape::Mesh {
+            path: relative_to(path, directory),
+            index: *index,
+        },
+        shape => shape.clone(),
+    }
+}
+
+fn absolute(path: &Path) -> PathBuf {
+    std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+// "path" from "directory" when it's inside, as it is otherwise
+fn relative_to(path: &Path, directory: &Path) -> PathBuf {
+    match absolute(path).strip_prefix(absolute(directory)) {
+        Ok(relative) => relative.to_path_buf(),
+        Err(_) => path.to_path_buf(),
+    }
 }
@@ -1,21 +1,69 @@
-use glam::Vec3;
+mod controller;
+mod fly;
+mod orbit;
+mod pan_zoom;
+mod projection;
+#[cfg(test)]
+mod tests;
 
+pub use controller::CameraController;
+use fly::Fly;
+use glam::{Mat4, Vec3};
+use orbit::Orbit;
+use pan_zoom::PanZoom;
+pub use projection::Projection;
+use serde::{Deserialize, Serialize};
+
+// Where the camera looks from and to, and how it projects what it sees
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub struct Camera {
     pub eye: Vec3,
     pub center: Vec3,
+    #[serde(default = "up")]
     pub up: Vec3,
+    #[serde(default = "Projection::perspective")]
+    pub projection: Projection,
 }
 
 impl Camera {
+    #[cfg(test)]
     pub fn new(eye: Vec3, center: Vec3) -> Camera {
         Camera {
             eye,
             center,
-            up: Vec3 {
-                x: 0.,
-                y: 1.,
-                z: 0.,
-            },
+            up: up(),
+            projection: Projection::perspective(),
         }
     }
+
+    pub fn view_matrix(&self) -> Mat4 {
+        Mat4::look_at_rh(self.eye, self.center, self.up)
+    }
+
+    // To Vulkan's clip space, for an image of "aspect_ratio" (width / height)
+    pub fn projection_matrix(&self, aspect_ratio: f32) -> Mat4 {
+        self.projection.matrix(aspect_ratio)
+    }
+
+    // Perspective, then infinite perspective, then orthographic (matching the perspective's size
+    // at the center), then perspective again
+    pub fn switch_projection(&mut self) {
+        self.projection = match self.projection {
+            Projection::Perspective { fov_y, near, .. } => {
+                Projection::InfinitePerspective { fov_y, near }
+            }
+            Projection::InfinitePerspective { fov_y, .. } => {
+                Projection::orthographic_matching(fov_y, self.eye.distance(self.center))
+            }
+            Projection::Orthographic { .. } => Projection::perspective(),
+        };
+    }
+}
+
+fn up() -> Vec3 {
+    Vec3 {
+        x: 0.,
+        y: 1.,
+        z: 0.,
+    }
 }
@@ -0,0 +1,102 @@
+use std::f32::consts::FRAC_PI_2;
+
+use glam::Vec3;
+
+use crate::app::input::{Action, Input};
+
+use super::{Camera, Fly, Orbit, PanZoom};
+
+// Radians turned per pixel of mouse motion
+pub const ROTATE_SENSITIVITY: f32 = 0.005;
+// Distance factor per wheel line
+pub const ZOOM_STEP: f32 = 1.1;
+// Closest the camera gets to what it looks at
+pub const MIN_DISTANCE: f32 = 0.05;
+// Looking straight up or down makes "up" undefined
+const PITCH_LIMIT: f32 = FRAC_PI_2 - 0.01;
+
+// Updates the camera from input, one way or another.
+// Switching starts the next controller from the camera as it is (nothing jumps)
+pub enum CameraController {
+    // Turn around the target
+    Orbit(Orbit),
+    // Move and look around freely
+    Fly(Fly),
+    // Slide and zoom, keeping the view direction
+    PanZoom(PanZoom),
+}
+
+impl CameraController {
+    pub fn orbit(camera: &Camera) -> CameraController {
+        CameraController::Orbit(Orbit::from_camera(camera))
+    }
+
+    // Orbit, then fly, then pan/zoom, then orbit again
+    pub fn next(&self, camera: &Camera) -> CameraController {
+        match self {
+            CameraController::Orbit(_) => CameraController::Fly(Fly::from_camera(camera)),
+            CameraController::Fly(_) => CameraController::PanZoom(PanZoom::from_camera(camera)),
+            CameraController::PanZoom(_) => CameraController::orbit(camera),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CameraController::Orbit(_) => "orbit",
+            CameraController::Fly(_) => "fly",
+            CameraController::PanZoom(_) => "pan/zoom",
+        }
+    }
+
+    // "dt" : seconds since the previous update
+    pub fn update(&mut self, camera: &mut Camera, input: &Input, dt: f32) {
+        match self {
+            CameraController::Orbit(orbit) => orbit.update(camera, input),
+            CameraController::Fly(fly) => fly.update(camera, input, dt),
+            CameraController::PanZoom(pan_zoom) => pan_zoom.update(camera, input),
+        }
+    }
+}
+
+// Unit vector : yaw around Y from +Z (towards +X), pitch above the XZ plane
+pub fn direction(yaw: f32, pitch: f32) -> Vec3 {
+    Vec3::new(
+        pitch.cos() * yaw.sin(),
+        pitch.sin(),
+        pitch.cos() * yaw.cos(),
+    )
+}
+
+// Inverse of "direction" ("direction" needn't be normalized)
+pub fn yaw_pitch(direction: Vec3) -> (f32, f32) {
+    let direction = direction.normalize_or(Vec3::Z);
+    let yaw = direction.x.atan2(direction.z);
+    let pitch = direction.y.clamp(-1., 1.).asin();
+    (yaw, clamp_pitch(pitch))
+}
+
+pub fn clamp_pitch(pitch: f32) -> f32 {
+    pitch.clamp(-PITCH_LIMIT, PITCH_LIMIT)
+}
+
+// Distance after scrolling "scroll" lines (away from the user : closer)
+pub fn zoomed(distance: f32, scroll: f32) -> f32 {
+    (distance * ZOOM_STEP.powf(-scroll)).max(MIN_DISTANCE)
+}
+
+// Rotating : while dragging, or always when the cursor is grabbed
+pub fn is_rotating(input: &Input) -> bool {
+    input.is_cursor_grabbed() || input.is_action_held(Action::Rotate)
+}
+
+// Multiplier from the speed modifiers held
+pub fn speed_factor(input: &Input) -> f32 {
+    let mut factor = 1.;
+    if input.is_action_held(Action::Fast) {
+        factor *= 4.;
+    }
+    if input.is_action_held(Action::Slow) {
+        factor *= 0.25;
+    }
+    factor
+}
@@ -0,0 +1,67 @@
+use glam::Vec3;
+
+use crate::app::input::{Action, Input};
+
+use super::{
+    controller::{self, ROTATE_SENSITIVITY},
+    Camera,
+};
+
+// Units per second, before speed modifiers
+const FLY_SPEED: f32 = 2.;
+
+// First person : moving along where the camera looks, looking around with the mouse
+pub struct Fly {
+    position: Vec3,
+    // Where the camera looks
+    yaw: f32,
+    pitch: f32,
+    // How far ahead the center stays (kept for the next controller)
+    focus_distance: f32,
+}
+
+impl Fly {
+    pub fn from_camera(camera: &Camera) -> Fly {
+        let forward = camera.center - camera.eye;
+        let (yaw, pitch) = controller::yaw_pitch(forward);
+        Fly {
+            position: camera.eye,
+            yaw,
+            pitch,
+            focus_distance: forward.length().max(controller::MIN_DISTANCE),
+        }
+    }
+
+    pub fn update(&mut self, camera: &mut Camera, input: &Input, dt: f32) {
+        // Look
+        if controller::is_rotating(input) {
+            let delta = input.mouse_delta() * ROTATE_SENSITIVITY;
+            self.yaw -= delta.x;
+            self.pitch = controller::clamp_pitch(self.pitch - delta.y);
+        }
+        let forward = controller::direction(self.yaw, self.pitch);
+        let right = forward.cross(Vec3::Y).normalize();
+
+        // Move (forward and sideways stay horizontal : looking down doesn't sink)
+        let ahead = Vec3::new(forward.x, 0., forward.z).normalize_or_zero();
+        let mut motion = Vec3::ZERO;
+        let moves = [
+            (Action::MoveForward, ahead),
+            (Action::MoveBackward, -ahead),
+            (Action::MoveRight, right),
+            (Action::MoveLeft, -right),
+            (Action::MoveUp, Vec3::Y),
+            (Action::MoveDown, -Vec3::Y),
+        ];
+        for (action, direction) in moves {
+            if input.is_action_held(action) {
+                motion += direction;
+            }
+        }
+        let speed = FLY_SPEED * controller::speed_factor(input);
+        self.position += motion.normalize_or_zero() * speed * dt;
+
+        camera.eye = self.position;
+        camera.center = self.position + self.focus_distance * forward;
+    }
+}
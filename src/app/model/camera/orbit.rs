@@ -0,0 +1,43 @@
+use glam::Vec3;
+
+use crate::app::input::Input;
+
+use super::{
+    controller::{self, ROTATE_SENSITIVITY},
+    Camera,
+};
+
+// Camera on a sphere around "target" : dragging turns around it, scrolling gets closer
+pub struct Orbit {
+    target: Vec3,
+    distance: f32,
+    // Where the camera stands, seen from the target
+    yaw: f32,
+    pitch: f32,
+}
+
+impl Orbit {
+    pub fn from_camera(camera: &Camera) -> Orbit {
+        let offset = camera.eye - camera.center;
+        let (yaw, pitch) = controller::yaw_pitch(offset);
+        Orbit {
+            target: camera.center,
+            distance: offset.length().max(controller::MIN_DISTANCE),
+            yaw,
+            pitch,
+        }
+    }
+
+    pub fn update(&mut self, camera: &mut Camera, input: &Input) {
+        // The scene follows the drag
+        if controller::is_rotating(input) {
+            let delta = input.mouse_delta() * ROTATE_SENSITIVITY;
+            self.yaw -= delta.x;
+            self.pitch = controller::clamp_pitch(self.pitch + delta.y);
+        }
+        self.distance = controller::zoomed(self.distance, input.scroll_delta().y);
+
+        camera.center = self.target;
+        camera.eye = self.target + self.distance * controller::direction(self.yaw, self.pitch);
+    }
+}
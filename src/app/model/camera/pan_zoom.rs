@@ -0,0 +1,41 @@
+use glam::Vec3;
+
+use crate::app::input::{Action, Input};
+
+use super::{controller, Camera};
+
+// Target moved per pixel dragged, per unit of distance (the target follows the cursor)
+const PAN_SENSITIVITY: f32 = 0.002;
+
+// View direction kept : dragging slides the camera and its target, scrolling gets closer
+pub struct PanZoom {
+    target: Vec3,
+    distance: f32,
+    // From the target to the camera
+    backward: Vec3,
+}
+
+impl PanZoom {
+    pub fn from_camera(camera: &Camera) -> PanZoom {
+        let offset = camera.eye - camera.center;
+        let (yaw, pitch) = controller::yaw_pitch(offset);
+        PanZoom {
+            target: camera.center,
+            distance: offset.length().max(controller::MIN_DISTANCE),
+            backward: controller::direction(yaw, pitch),
+        }
+    }
+
+    pub fn update(&mut self, camera: &mut Camera, input: &Input) {
+        if input.is_action_held(Action::Pan) || input.is_action_held(Action::Rotate) {
+            let right = Vec3::Y.cross(self.backward).normalize();
+            let up = self.backward.cross(right);
+            let delta = input.mouse_delta() * PAN_SENSITIVITY * self.distance;
+            self.target += -delta.x * right + delta.y * up;
+        }
+        self.distance = controller::zoomed(self.distance, input.scroll_delta().y);
+
+        camera.center = self.target;
+        camera.eye = self.target + self.distance * self.backward;
+    }
+}
@@ -0,0 +1,80 @@
+use std::f32::consts::FRAC_PI_4;
+
+use glam::{Mat4, Vec3, Vec4};
+use serde::{Deserialize, Serialize};
+
+// Vulkan's clip space has Y pointing down (and depth in [0, 1])
+const VULKAN_Y_FLIP: Mat4 = Mat4::from_cols(Vec4::X, Vec4::NEG_Y, Vec4::Z, Vec4::W);
+
+// How the camera's view becomes Vulkan clip space.
+// Depth is reversed (near : 1, far : 0) : floats are precise where depth needs it most,
+// the renderer clears depth to 0 and keeps greater depths
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub enum Projection {
+    // "fov_y" : vertical field of view, in radians
+    Perspective {
+        fov_y: f32,
+        near: f32,
+        far: f32,
+    },
+    // Perspective without far plane (terrain, skies)
+    InfinitePerspective {
+        fov_y: f32,
+        near: f32,
+    },
+    // Parallel projection showing "view_height" units vertically (technical drawings)
+    Orthographic {
+        view_height: f32,
+        near: f32,
+        far: f32,
+    },
+}
+
+impl Projection {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Projection::Perspective { .. } => "perspective",
+            Projection::InfinitePerspective { .. } => "infinite perspective",
+            Projection::Orthographic { .. } => "orthographic",
+        }
+    }
+
+    pub fn perspective() -> Projection {
+        Projection::Perspective {
+            fov_y: FRAC_PI_4,
+            near: 0.1,
+            far: 100.,
+        }
+    }
+
+    // Orthographic view showing about what "perspective" shows at "distance" from the camera
+    pub fn orthographic_matching(fov_y: f32, distance: f32) -> Projection {
+        Projection::Orthographic {
+            view_height: 2. * distance * (fov_y / 2.).tan(),
+            near: -100.,
+            far: 100.,
+        }
+    }
+
+    // "aspect_ratio" : width / height of the rendered image
+    pub fn matrix(&self, aspect_ratio: f32) -> Mat4 {
+        let projection = match *self {
+            // near and far swapped : reversed depth
+            Projection::Perspective { fov_y, near, far } => {
+                Mat4::perspective_rh(fov_y, aspect_ratio, far, near)
+            }
+            Projection::InfinitePerspective { fov_y, near } => {
+                Mat4::perspective_infinite_reverse_rh(fov_y, aspect_ratio, near)
+            }
+            Projection::Orthographic {
+                view_height,
+                near,
+                far,
+            } => {
+                let half = Vec3::new(aspect_ratio * view_height, view_height, 0.) / 2.;
+                Mat4::orthographic_rh(-half.x, half.x, -half.y, half.y, far, near)
+            }
+        };
+        VULKAN_Y_FLIP * projection
+    }
+}
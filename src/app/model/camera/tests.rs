@@ -0,0 +1,145 @@
+use glam::{Vec3, Vec4};
+use winit::{
+    event::{DeviceEvent, DeviceId, MouseButton, MouseScrollDelta, TouchPhase, WindowEvent},
+    keyboard::KeyCode,
+};
+
+use crate::app::input::{Binding, Bindings, Input};
+
+use super::{controller::MIN_DISTANCE, Camera, CameraController, Projection};
+
+const EPSILON: f32 = 1e-4;
+
+fn camera() -> Camera {
+    Camera::new(Vec3::new(1., 2., 3.), Vec3::new(0., 0.5, 0.))
+}
+
+fn assert_vec3_eq(actual: Vec3, expected: Vec3) {
+    assert!(
+        actual.abs_diff_eq(expected, EPSILON),
+        "{actual} != {expected}"
+    );
+}
+
+fn dragging(delta: (f64, f64), button: MouseButton) -> Input {
+    let mut input = Input::new(Bindings::default_bindings());
+    input.press(Binding::Mouse(button));
+    input.handle_device_event(&DeviceEvent::MouseMotion { delta });
+    input
+}
+
+// Switching controllers doesn't move the camera
+#[test]
+fn controllers_start_from_the_camera() {
+    let input = Input::new(Bindings::default_bindings());
+    let mut controller = CameraController::orbit(&camera());
+    for _ in 0..3 {
+        let mut moved = camera();
+        controller.update(&mut moved, &input, 0.1);
+        assert_vec3_eq(moved.eye, camera().eye);
+        assert_vec3_eq(moved.center, camera().center);
+        controller = controller.next(&moved);
+    }
+    assert_eq!(controller.name(), "orbit");
+}
+
+#[test]
+fn orbit_keeps_distance_to_target() {
+    let mut moved = camera();
+    let mut orbit = CameraController::orbit(&moved);
+    orbit.update(&mut moved, &dragging((40., -25.), MouseButton::Left), 0.1);
+    assert_vec3_eq(moved.center, camera().center);
+    let distance = (camera().eye - camera().center).length();
+    assert!(((moved.eye - moved.center).length() - distance).abs() < EPSILON);
+    assert!(moved.eye.distance(camera().eye) > 0.1);
+}
+
+#[test]
+fn fly_moves_horizontally_at_speed() {
+    let start = Camera::new(Vec3::ZERO, Vec3::new(0., -1., -1.));
+    let mut moved = Camera::new(start.eye, start.center);
+    let mut fly = CameraController::orbit(&start).next(&start);
+    let mut input = Input::new(Bindings::default_bindings());
+    input.press(Binding::Key(KeyCode::KeyW));
+    fly.update(&mut moved, &input, 0.5);
+    assert_vec3_eq(moved.eye, Vec3::new(0., 0., -1.));
+    assert_vec3_eq(moved.center - moved.eye, start.center - start.eye);
+
+    // Faster, sideways
+    input.press(Binding::Key(KeyCode::ControlLeft));
+    input.release(Binding::Key(KeyCode::KeyW));
+    input.press(Binding::Key(KeyCode::KeyD));
+    fly.update(&mut moved, &input, 0.5);
+    assert_vec3_eq(moved.eye, Vec3::new(4., 0., -1.));
+}
+
+#[test]
+fn pan_keeps_view_direction() {
+    let mut moved = camera();
+    let mut pan_zoom = CameraController::orbit(&moved).next(&moved).next(&moved);
+    pan_zoom.update(&mut moved, &dragging((30., 10.), MouseButton::Right), 0.1);
+    assert_vec3_eq(moved.eye - moved.center, camera().eye - camera().center);
+    assert!(moved.center.distance(camera().center) > 0.01);
+}
+
+#[test]
+fn zoom_stops_before_target() {
+    let mut moved = camera();
+    let mut orbit = CameraController::orbit(&moved);
+    let mut input = Input::new(Bindings::default_bindings());
+    input.handle_window_event(&WindowEvent::MouseWheel {
+        device_id: DeviceId::dummy(),
+        delta: MouseScrollDelta::LineDelta(0., 100.),
+        phase: TouchPhase::Moved,
+    });
+    orbit.update(&mut moved, &input, 0.1);
+    let distance = (moved.eye - moved.center).length();
+    assert!((distance - MIN_DISTANCE).abs() < EPSILON);
+}
+
+// Clip space position of "point" (world) divided by w
+fn project(camera: &Camera, point: Vec3) -> Vec3 {
+    let clip = camera.projection_matrix(1.) * camera.view_matrix() * point.extend(1.);
+    clip.truncate() / clip.w
+}
+
+// Vulkan : Y down, depth in [0, 1], reversed (near 1, far 0)
+#[test]
+fn projections_follow_vulkan_clip_space() {
+    let mut camera = Camera::new(Vec3::new(0., 0., 5.), Vec3::ZERO);
+    let projections = [
+        Projection::Perspective {
+            fov_y: 1.,
+            near: 1.,
+            far: 9.,
+        },
+        Projection::Orthographic {
+            view_height: 4.,
+            near: 1.,
+            far: 9.,
+        },
+    ];
+    for projection in projections {
+        camera.projection = projection;
+        assert!((project(&camera, Vec3::new(0., 0., 4.)).z - 1.).abs() < EPSILON);
+        assert!(project(&camera, Vec3::new(0., 0., -4.)).z.abs() < EPSILON);
+        assert!(project(&camera, Vec3::new(0., 1., 0.)).y < 0.);
+    }
+
+    // Orthographic : "view_height" units fill the image vertically
+    assert!((project(&camera, Vec3::new(0., -2., 0.)).y - 1.).abs() < EPSILON);
+}
+
+#[test]
+fn infinite_perspective_never_clips_far() {
+    let mut camera = Camera::new(Vec3::ZERO, Vec3::NEG_Z);
+    camera.projection = Projection::InfinitePerspective {
+        fov_y: 1.,
+        near: 0.5,
+    };
+    assert!((project(&camera, Vec3::new(0., 0., -0.5)).z - 1.).abs() < EPSILON);
+    let far = project(&camera, Vec3::new(0., 0., -1e6)).z;
+    assert!(far > 0. && far < 1e-5);
+    let clip = camera.projection_matrix(1.) * Vec4::new(0., 0., -1e6, 1.);
+    assert!(clip.w > 0.);
+}
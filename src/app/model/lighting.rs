@@ -0,0 +1,34 @@
+use glam::Vec3;
+use serde::{Deserialize, Serialize};
+
+// The renderer shades with at most this many directional lights (the others are ignored),
+// defined as MAX_LIGHTS when compiling the shaders
+pub const MAX_LIGHTS: usize = 4;
+
+// Light coming from "direction" (towards the light, needn't be normalized) everywhere, like the sun
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub struct DirectionalLight {
+    pub direction: Vec3,
+    pub color: Vec3,
+}
+
+// How objects are lit : a color reaching every face, plus what the lights bring to the faces
+// turned towards them
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Lighting {
+    pub ambient: Vec3,
+    pub lights: Vec<DirectionalLight>,
+}
+
+impl Lighting {
+    // White light from above, front and right, faces turned away still a bit visible
+    pub fn default_lighting() -> Lighting {
+        Lighting {
+            ambient: Vec3::splat(0.3),
+            lights: vec![DirectionalLight {
+                direction: Vec3::new(1., 2., 3.),
+                color: Vec3::splat(0.7),
+            }],
+        }
+    }
+}
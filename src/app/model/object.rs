@@ -1,5 +1,23 @@
+mod capsule;
+mod cone;
 mod cube;
+mod cylinder;
+mod icosphere;
+mod mesh_object;
 mod octahedron;
+mod placed;
+mod plane;
+mod torus;
+mod uv_sphere;
 
+pub use capsule::Capsule;
+pub use cone::Cone;
 pub use cube::Cube;
+pub use cylinder::Cylinder;
+pub use icosphere::Icosphere;
+pub use mesh_object::MeshObject;
 pub use octahedron::Octahedron;
+pub use placed::Placed;
+pub use plane::Plane;
+pub use torus::Torus;
+pub use uv_sphere::UvSphere;
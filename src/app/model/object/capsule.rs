@@ -0,0 +1,49 @@
+use super::super::space::{Coord, Orientation};
+
+// Cylinder of radius 1 and height "length" along Y closed by two hemispheres,
+// made of "rings" latitude bands per hemisphere and "segments" longitude slices
+pub struct Capsule {
+    pub position: Coord,
+    pub orientation: Orientation,
+    pub size: f32,
+    pub rings: u32,
+    pub segments: u32,
+    pub length: f32,
+}
+
+impl Capsule {
+    pub fn new(
+        position: Coord,
+        orientation: Orientation,
+        size: f32,
+        rings: u32,
+        segments: u32,
+        length: f32,
+    ) -> Capsule {
+        Capsule {
+            position,
+            orientation,
+            size,
+            rings,
+            segments,
+            length,
+        }
+    }
+
+    pub fn new_unoriented(
+        position: Coord,
+        size: f32,
+        rings: u32,
+        segments: u32,
+        length: f32,
+    ) -> Capsule {
+        Capsule::new(
+            position,
+            Orientation::initial(),
+            size,
+            rings,
+            segments,
+            length,
+        )
+    }
+}
@@ -0,0 +1,24 @@
+use super::super::space::{Coord, Orientation};
+
+// Capped cone of radius 1 and height 2 along Y (apex up), made of "segments" slices
+pub struct Cone {
+    pub position: Coord,
+    pub orientation: Orientation,
+    pub size: f32,
+    pub segments: u32,
+}
+
+impl Cone {
+    pub fn new(position: Coord, orientation: Orientation, size: f32, segments: u32) -> Cone {
+        Cone {
+            position,
+            orientation,
+            size,
+            segments,
+        }
+    }
+
+    pub fn new_unoriented(position: Coord, size: f32, segments: u32) -> Cone {
+        Cone::new(position, Orientation::initial(), size, segments)
+    }
+}
@@ -0,0 +1,24 @@
+use super::super::space::{Coord, Orientation};
+
+// Capped cylinder of radius 1 and height 2 along Y, made of "segments" slices
+pub struct Cylinder {
+    pub position: Coord,
+    pub orientation: Orientation,
+    pub size: f32,
+    pub segments: u32,
+}
+
+impl Cylinder {
+    pub fn new(position: Coord, orientation: Orientation, size: f32, segments: u32) -> Cylinder {
+        Cylinder {
+            position,
+            orientation,
+            size,
+            segments,
+        }
+    }
+
+    pub fn new_unoriented(position: Coord, size: f32, segments: u32) -> Cylinder {
+        Cylinder::new(position, Orientation::initial(), size, segments)
+    }
+}
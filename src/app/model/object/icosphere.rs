@@ -0,0 +1,29 @@
+use super::super::space::{Coord, Orientation};
+
+// Sphere of radius 1 made by subdividing an icosahedron "subdivisions" times
+pub struct Icosphere {
+    pub position: Coord,
+    pub orientation: Orientation,
+    pub size: f32,
+    pub subdivisions: u32,
+}
+
+impl Icosphere {
+    pub fn new(
+        position: Coord,
+        orientation: Orientation,
+        size: f32,
+        subdivisions: u32,
+    ) -> Icosphere {
+        Icosphere {
+            position,
+            orientation,
+            size,
+            subdivisions,
+        }
+    }
+
+    pub fn new_unoriented(position: Coord, size: f32, subdivisions: u32) -> Icosphere {
+        Icosphere::new(position, Orientation::initial(), size, subdivisions)
+    }
+}
@@ -0,0 +1,43 @@
+use crate::app::graphics_engine::{Material, Mesh, MeshKey};
+
+use super::super::space::{Coord, Orientation};
+
+// Object whose mesh isn't generated but given (loaded from a file)
+// The mesh can't be changed in place : the key would still name the uploaded geometry
+#[derive(Clone)]
+pub struct MeshObject {
+    pub position: Coord,
+    pub orientation: Orientation,
+    pub size: f32,
+    pub material: Material,
+    pub(crate) mesh: Mesh,
+    pub(crate) mesh_key: MeshKey,
+}
+
+impl MeshObject {
+    pub fn new(position: Coord, orientation: Orientation, size: f32, mesh: Mesh) -> MeshObject {
+        MeshObject::with_key(position, orientation, size, mesh, MeshKey::unique())
+    }
+
+    // Objects sharing "mesh" (instances of the same file mesh) share its key
+    pub fn with_key(
+        position: Coord,
+        orientation: Orientation,
+        size: f32,
+        mesh: Mesh,
+        mesh_key: MeshKey,
+    ) -> MeshObject {
+        MeshObject {
+            position,
+            orientation,
+            size,
+            material: Material::plain(),
+            mesh,
+            mesh_key,
+        }
+    }
+
+    pub fn new_unoriented(position: Coord, size: f32, mesh: Mesh) -> MeshObject {
+        MeshObject::new(position, Orientation::initial(), size, mesh)
+    }
+}
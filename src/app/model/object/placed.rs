@@ -0,0 +1,10 @@
+use glam::Mat4;
+
+use crate::app::graphics_engine::ToMesh;
+
+// "object" drawn at "transform" instead of its own
+// (placed by a scene node, or between two simulation ticks)
+pub struct Placed<'a> {
+    pub object: &'a dyn ToMesh,
+    pub transform: Mat4,
+}
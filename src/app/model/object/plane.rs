@@ -0,0 +1,24 @@
+use super::super::space::{Coord, Orientation};
+
+// Square of side 2 in the XZ plane facing +Y, split in "subdivisions" x "subdivisions" quads
+pub struct Plane {
+    pub position: Coord,
+    pub orientation: Orientation,
+    pub size: f32,
+    pub subdivisions: u32,
+}
+
+impl Plane {
+    pub fn new(position: Coord, orientation: Orientation, size: f32, subdivisions: u32) -> Plane {
+        Plane {
+            position,
+            orientation,
+            size,
+            subdivisions,
+        }
+    }
+
+    pub fn new_unoriented(position: Coord, size: f32, subdivisions: u32) -> Plane {
+        Plane::new(position, Orientation::initial(), size, subdivisions)
+    }
+}
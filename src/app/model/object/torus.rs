@@ -0,0 +1,49 @@
+use super::super::space::{Coord, Orientation};
+
+// Torus around Y with a ring of radius 1 and a tube of radius "thickness",
+// made of "rings" slices around the ring and "segments" slices around the tube
+pub struct Torus {
+    pub position: Coord,
+    pub orientation: Orientation,
+    pub size: f32,
+    pub rings: u32,
+    pub segments: u32,
+    pub thickness: f32,
+}
+
+impl Torus {
+    pub fn new(
+        position: Coord,
+        orientation: Orientation,
+        size: f32,
+        rings: u32,
+        segments: u32,
+        thickness: f32,
+    ) -> Torus {
+        Torus {
+            position,
+            orientation,
+            size,
+            rings,
+            segments,
+            thickness,
+        }
+    }
+
+    pub fn new_unoriented(
+        position: Coord,
+        size: f32,
+        rings: u32,
+        segments: u32,
+        thickness: f32,
+    ) -> Torus {
+        Torus::new(
+            position,
+            Orientation::initial(),
+            size,
+            rings,
+            segments,
+            thickness,
+        )
+    }
+}
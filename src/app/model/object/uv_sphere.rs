@@ -0,0 +1,32 @@
+use super::super::space::{Coord, Orientation};
+
+// Sphere of radius 1 made of "rings" latitude bands and "segments" longitude slices
+pub struct UvSphere {
+    pub position: Coord,
+    pub orientation: Orientation,
+    pub size: f32,
+    pub rings: u32,
+    pub segments: u32,
+}
+
+impl UvSphere {
+    pub fn new(
+        position: Coord,
+        orientation: Orientation,
+        size: f32,
+        rings: u32,
+        segments: u32,
+    ) -> UvSphere {
+        UvSphere {
+            position,
+            orientation,
+            size,
+            rings,
+            segments,
+        }
+    }
+
+    pub fn new_unoriented(position: Coord, size: f32, rings: u32, segments: u32) -> UvSphere {
+        UvSphere::new(position, Orientation::initial(), size, rings, segments)
+    }
+}
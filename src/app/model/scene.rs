@@ -0,0 +1,148 @@
+#[cfg(test)]
+mod tests;
+
+use glam::{Mat4, Vec3};
+
+use crate::app::graphics_engine::ToMesh;
+
+use super::{object::Placed, space::Orientation, world::Velocity};
+
+// Position, rotation and scale relative to the parent node
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Transform {
+    pub translation: Vec3,
+    pub rotation: Orientation,
+    pub scale: Vec3,
+}
+
+impl Transform {
+    // Uniformly scaled by "size"
+    pub fn new(translation: Vec3, rotation: Orientation, size: f32) -> Transform {
+        Transform {
+            translation,
+            rotation,
+            scale: Vec3::splat(size),
+        }
+    }
+
+    pub fn matrix(&self) -> Mat4 {
+        Mat4::from_scale_rotation_translation(self.scale, self.rotation.to_quat(), self.translation)
+    }
+}
+
+// Index of a node in its scene graph
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct NodeId(usize);
+
+struct Node {
+    local: Transform,
+    // local composed with the parents', valid when not dirty
+    world: Mat4,
+    dirty: bool,
+    parent: Option<NodeId>,
+    children: Vec<NodeId>,
+    // Applied to "local" each tick
+    velocity: Option<Velocity>,
+    // Drawn relative to the node (its own transform composed with the node's world one)
+    drawables: Vec<Box<dyn ToMesh>>,
+}
+
+// Nodes placed relative to their parent : moving a node moves its subtree (arms, moons).
+// Changing a local transform marks the node dirty, world transforms of dirty subtrees are
+// recomputed by "update_world_transforms" (to call before reading them)
+pub struct SceneGraph {
+    nodes: Vec<Node>,
+    roots: Vec<NodeId>,
+}
+
+impl SceneGraph {
+    pub fn new() -> SceneGraph {
+        SceneGraph {
+            nodes: Vec::new(),
+            roots: Vec::new(),
+        }
+    }
+
+    // "parent" : None for a root node
+    pub fn add_node(&mut self, parent: Option<NodeId>, local: Transform) -> NodeId {
+        let id = NodeId(self.nodes.len());
+        self.nodes.push(Node {
+            local,
+            world: Mat4::IDENTITY,
+            dirty: true,
+            parent,
+            children: Vec::new(),
+            velocity: None,
+            drawables: Vec::new(),
+        });
+        match parent {
+            Some(parent) => self.nodes[parent.0].children.push(id),
+            None => self.roots.push(id),
+        }
+        id
+    }
+
+    pub fn attach(&mut self, node: NodeId, drawable: Box<dyn ToMesh>) {
+        self.nodes[node.0].drawables.push(drawable);
+    }
+
+    // The node (and its subtree) moves by "velocity" each tick
+    pub fn set_velocity(&mut self, node: NodeId, velocity: Velocity) {
+        self.nodes[node.0].velocity = Some(velocity);
+    }
+
+    pub fn velocity(&self, node: NodeId) -> Option<Velocity> {
+        self.nodes[node.0].velocity
+    }
+
+    // Advance nodes having a velocity by "dt" seconds (marked dirty)
+    pub fn tick(&mut self, dt: f32) {
+        for node in &mut self.nodes {
+            if let Some(velocity) = node.velocity {
+                velocity.apply(&mut node.local, dt);
+                node.dirty = true;
+            }
+        }
+    }
+
+    // Recompute world transforms of dirty nodes and their subtrees (the others are kept)
+    pub fn update_world_transforms(&mut self) {
+        for idx in 0..self.roots.len() {
+            self.update_subtree(self.roots[idx], Mat4::IDENTITY, false);
+        }
+    }
+
+    fn update_subtree(&mut self, id: NodeId, parent_world: Mat4, parent_changed: bool) {
+        let node = &mut self.nodes[id.0];
+        let changed = node.dirty || parent_changed;
+        if changed {
+            node.world = parent_world * node.local.matrix();
+            node.dirty = false;
+        }
+        let world = node.world;
+        for idx in 0..self.nodes[id.0].children.len() {
+            self.update_subtree(self.nodes[id.0].children[idx], world, changed);
+        }
+    }
+
+    // Every drawable, at its world transform (as of the last update)
+    pub fn drawables(&self) -> impl Iterator<Item = Placed<'_>> {
+        self.nodes.iter().flat_map(|node| {
+            node.drawables.iter().map(|drawable| Placed {
+                object: drawable.as_ref(),
+                transform: node.world * drawable.transform(),
+            })
+        })
+    }
+}
+
+// Hierarchy queries
+impl SceneGraph {
+    pub fn parent(&self, node: NodeId) -> Option<NodeId> {
+        self.nodes[node.0].parent
+    }
+
+    pub fn local(&self, node: NodeId) -> &Transform {
+        &self.nodes[node.0].local
+    }
+}
@@ -0,0 +1,136 @@
+use glam::{Mat4, Quat, Vec3};
+
+use crate::app::model::{
+    object::Cube,
+    space::{Coord, Orientation},
+    world::Velocity,
+};
+
+use super::{NodeId, SceneGraph, Transform};
+
+fn assert_mat4_eq(actual: Mat4, expected: Mat4) {
+    assert!(actual.abs_diff_eq(expected, 1e-5), "{actual} != {expected}");
+}
+
+fn translation(x: f32, y: f32, z: f32) -> Transform {
+    Transform::new(Vec3::new(x, y, z), Orientation::initial(), 1.)
+}
+
+// A unit cube at "node"'s origin, to observe where it is drawn
+fn mark(scene: &mut SceneGraph, node: NodeId) {
+    scene.attach(
+        node,
+        Box::new(Cube::new_unoriented(Coord::new(0., 0., 0.), 1.)),
+    );
+}
+
+// Where the marks are, in the order of their nodes (as of the last update)
+fn marks(scene: &SceneGraph) -> Vec<Vec3> {
+    scene
+        .drawables()
+        .map(|placed| placed.transform.transform_point3(Vec3::ZERO))
+        .collect()
+}
+
+fn assert_positions_eq(actual: &[Vec3], expected: &[Vec3]) {
+    assert_eq!(actual.len(), expected.len());
+    for (actual, expected) in actual.iter().zip(expected) {
+        assert!(
+            actual.abs_diff_eq(*expected, 1e-5),
+            "{actual} != {expected}"
+        );
+    }
+}
+
+// Arm : shoulder turned a quarter, elbow one unit further along the arm
+#[test]
+fn world_transforms_compose_down_the_tree() {
+    let mut scene = SceneGraph::new();
+    let shoulder = scene.add_node(
+        None,
+        Transform::new(
+            Vec3::new(0., 1., 0.),
+            Orientation::from_quat(Quat::from_rotation_z(std::f32::consts::FRAC_PI_2)),
+            1.,
+        ),
+    );
+    let elbow = scene.add_node(Some(shoulder), translation(1., 0., 0.));
+    mark(&mut scene, elbow);
+    scene.update_world_transforms();
+
+    assert_positions_eq(&marks(&scene), &[Vec3::new(0., 2., 0.)]);
+    assert_eq!(scene.parent(elbow), Some(shoulder));
+    assert_eq!(scene.parent(shoulder), None);
+}
+
+// Only dirty subtrees are recomputed, moving a parent moves its children
+#[test]
+fn dirty_flags_propagate() {
+    let mut scene = SceneGraph::new();
+    let planet = scene.add_node(None, translation(1., 0., 0.));
+    let moon = scene.add_node(Some(planet), translation(0., 0., 1.));
+    let other = scene.add_node(None, translation(5., 0., 0.));
+    mark(&mut scene, moon);
+    mark(&mut scene, other);
+    scene.set_velocity(
+        planet,
+        Velocity {
+            linear: Vec3::X,
+            angular: Vec3::ZERO,
+        },
+    );
+    scene.update_world_transforms();
+
+    scene.tick(1.);
+    assert_eq!(scene.local(planet).translation, Vec3::new(2., 0., 0.));
+    // Not updated yet
+    assert_positions_eq(
+        &marks(&scene),
+        &[Vec3::new(1., 0., 1.), Vec3::new(5., 0., 0.)],
+    );
+    scene.update_world_transforms();
+    assert_positions_eq(
+        &marks(&scene),
+        &[Vec3::new(2., 0., 1.), Vec3::new(5., 0., 0.)],
+    );
+}
+
+// A spinning planet carries its moon around it
+#[test]
+fn velocities_move_subtrees() {
+    let mut scene = SceneGraph::new();
+    let planet = scene.add_node(None, translation(0., 1., 0.));
+    let moon = scene.add_node(Some(planet), translation(1., 0., 0.));
+    mark(&mut scene, moon);
+    scene.set_velocity(planet, Velocity::spinning(std::f32::consts::PI * Vec3::Y));
+    scene.update_world_transforms();
+
+    // Half a turn in two ticks
+    scene.tick(0.5);
+    scene.tick(0.5);
+    scene.update_world_transforms();
+    assert_positions_eq(&marks(&scene), &[Vec3::new(-1., 1., 0.)]);
+}
+
+// Drawables keep their own transform, relative to their node
+#[test]
+fn drawables_are_placed_by_their_node() {
+    let mut scene = SceneGraph::new();
+    let node = scene.add_node(None, translation(0., 2., 0.));
+    scene.attach(
+        node,
+        Box::new(Cube::new_unoriented(Coord::new(1., 0., 0.), 0.5)),
+    );
+    scene.update_world_transforms();
+
+    let placed: Vec<_> = scene.drawables().collect();
+    assert_eq!(placed.len(), 1);
+    assert_mat4_eq(
+        placed[0].transform,
+        Mat4::from_scale_rotation_translation(
+            Vec3::splat(0.5),
+            Quat::IDENTITY,
+            Vec3::new(1., 2., 0.),
+        ),
+    );
+}
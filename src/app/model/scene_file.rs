@@ -0,0 +1,456 @@
+#[cfg(test)]
+mod tests;
+
+use std::{
+    collections::HashMap,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use ron::{extensions::Extensions, ser::PrettyConfig, Options};
+use serde::{Deserialize, Serialize};
+
+use crate::app::graphics_engine::{load_gltf, load_obj, ToMesh};
+
+use super::{
+    camera::Camera,
+    lighting::{Lighting, MAX_LIGHTS},
+    object::{
+        Capsule, Cone, Cube, Cylinder, Icosphere, MeshObject, Octahedron, Plane, Torus, UvSphere,
+    },
+    space::{Coord, Orientation},
+    world::{Material, Velocity},
+};
+
+// Version written in saved scenes. Bump it when a change breaks older files
+// (and convert those in "parse")
+pub const SCENE_VERSION: u32 = 1;
+
+// Most subdivisions of an icosphere (each one multiplies its triangles by 4)
+const MAX_SUBDIVISIONS: u32 = 7;
+// Most rings, segments or subdivisions of the other shapes : grids of about a million vertices
+// at most (vertex and index counts fit in u32)
+const MAX_DIVISIONS: u32 = 1024;
+
+// A model as written in a scene file, in RON. For example :
+// (
+//     version: 1,
+//     camera: (eye: (2, 2, 2), center: (0, 0, 0)),
+//     objects: [
+//         (name: "cube", shape: Cube, position: (0, 0.5, 0), size: 0.25),
+//     ],
+// )
+// Camera "up" and "projection", "lighting", "objects" and "nodes" can be left out,
+// so can objects' "orientation", "size", "material" and "velocity"
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct SceneDescription {
+    pub version: u32,
+    pub camera: Camera,
+    #[serde(default = "Lighting::default_lighting")]
+    pub lighting: Lighting,
+    #[serde(default)]
+    pub objects: Vec<ObjectDescription>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub nodes: Vec<NodeDescription>,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct ObjectDescription {
+    pub name: String,
+    pub shape: Shape,
+    #[serde(default = "origin")]
+    pub position: Coord,
+    #[serde(default = "Orientation::initial")]
+    pub orientation: Orientation,
+    #[serde(default = "unit")]
+    pub size: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub material: Option<Material>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub velocity: Option<Velocity>,
+}
+
+// A scene graph node, placed relative to its "parent" (the index of a node listed before it) :
+// moving or spinning it moves its objects (placed relative to it, without velocity) and its
+// children along. For example, a moon around a spinning planet :
+// nodes: [
+//     (name: "planet", velocity: (angular: (0, 1, 0)), objects: [(name: "planet", shape: Cube)]),
+//     (name: "moon", parent: 0, position: (2, 0, 0)),
+// ]
+// "parent", "position", "orientation", "size", "velocity" and "objects" can be left out
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct NodeDescription {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<usize>,
+    #[serde(default = "origin")]
+    pub position: Coord,
+    #[serde(default = "Orientation::initial")]
+    pub orientation: Orientation,
+    #[serde(default = "unit")]
+    pub size: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub velocity: Option<Velocity>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub objects: Vec<ObjectDescription>,
+}
+
+// Geometry of an object, at unit size (scaled by the object's "size").
+// Entities built from a description keep theirs, to be saved again
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum Shape {
+    Octahedron,
+    Cube,
+    UvSphere {
+        rings: u32,
+        segments: u32,
+    },
+    Icosphere {
+        subdivisions: u32,
+    },
+    Cylinder {
+        segments: u32,
+    },
+    Cone {
+        segments: u32,
+    },
+    Torus {
+        rings: u32,
+        segments: u32,
+        thickness: f32,
+    },
+    Plane {
+        subdivisions: u32,
+    },
+    Capsule {
+        rings: u32,
+        segments: u32,
+        length: f32,
+    },
+    // Object "index" of an OBJ (only 0) or glTF file,
+    // "path" relative to the scene file's directory
+    Mesh {
+        path: PathBuf,
+        #[serde(default)]
+        index: usize,
+    },
+}
+
+#[derive(Debug)]
+pub enum SceneError {
+    Io(PathBuf, io::Error),
+    // Not RON, or not shaped like a scene (unknown shape, missing field...)
+    Syntax(ron::error::SpannedError),
+    MissingVersion,
+    // Written by a newer version of the app, or 0 (versions start at 1)
+    UnsupportedVersion(u32),
+    // Read, but meaningless (negative size, too many lights...)
+    Invalid(String),
+    // A Mesh shape's file
+    Mesh(PathBuf, String),
+    Write(ron::Error),
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SceneError::Io(path, err) => write!(f, "{} : {err}", path.display()),
+            SceneError::Syntax(err) => write!(
+                f,
+                "line {}, column {} : {}",
+                err.position.line, err.position.col, err.code
+            ),
+            SceneError::MissingVersion => {
+                write!(f, "no \"version\" (add \"version: {SCENE_VERSION},\")")
+            }
+            SceneError::UnsupportedVersion(0) => {
+                write!(f, "no version 0 (versions start at 1)")
+            }
+            SceneError::UnsupportedVersion(version) => write!(
+                f,
+                "version {version} is newer than this app's ({SCENE_VERSION})"
+            ),
+            SceneError::Invalid(message) => write!(f, "{message}"),
+            SceneError::Mesh(path, message) => write!(f, "{} : {message}", path.display()),
+            SceneError::Write(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for SceneError {}
+
+impl From<ron::error::SpannedError> for SceneError {
+    fn from(err: ron::error::SpannedError) -> SceneError {
+        SceneError::Syntax(err)
+    }
+}
+
+impl SceneDescription {
+    // Checked (see "validate")
+    pub fn read(path: &Path) -> Result<SceneDescription, SceneError> {
+        let text =
+            fs::read_to_string(path).map_err(|err| SceneError::Io(path.to_path_buf(), err))?;
+        let scene = SceneDescription::parse(&text)?;
+        scene.validate()?;
+        Ok(scene)
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), SceneError> {
+        let text = self.to_text()?;
+        fs::write(path, text).map_err(|err| SceneError::Io(path.to_path_buf(), err))
+    }
+
+    pub fn parse(text: &str) -> Result<SceneDescription, SceneError> {
+        // The version alone first : a file of another version isn't reported as malformed
+        #[derive(Deserialize)]
+        struct Header {
+            #[serde(default)]
+            version: Option<u32>,
+        }
+        let header: Header = options().from_str(text)?;
+        match header.version {
+            None => Err(SceneError::MissingVersion),
+            Some(version) if version == 0 || version > SCENE_VERSION => {
+                Err(SceneError::UnsupportedVersion(version))
+            }
+            Some(_) => Ok(options().from_str(text)?),
+        }
+    }
+
+    pub fn to_text(&self) -> Result<String, SceneError> {
+        options()
+            .to_string_pretty(self, PrettyConfig::new())
+            .map_err(SceneError::Write)
+    }
+
+    // What parsing can't tell : values out of their range
+    pub fn validate(&self) -> Result<(), SceneError> {
+        if self.camera.eye == self.camera.center {
+            return Err(SceneError::Invalid(
+                "camera : \"eye\" and \"center\" are the same point".to_string(),
+            ));
+        }
+        if self.lighting.lights.len() > MAX_LIGHTS {
+            return Err(SceneError::Invalid(format!(
+                "lighting : {} lights, at most {MAX_LIGHTS} are supported",
+                self.lighting.lights.len()
+            )));
+        }
+        for object in &self.objects {
+            object.validate().map_err(|message| {
+                SceneError::Invalid(format!("object \"{}\" : {message}", object.name))
+            })?;
+        }
+        for (idx, node) in self.nodes.iter().enumerate() {
+            node.validate(idx).map_err(|message| {
+                SceneError::Invalid(format!("node \"{}\" : {message}", node.name))
+            })?;
+        }
+        Ok(())
+    }
+}
+
+impl ObjectDescription {
+    fn validate(&self) -> Result<(), String> {
+        validate_size(self.size)?;
+        self.shape.validate()
+    }
+}
+
+impl NodeDescription {
+    // "idx" : its index in the scene's nodes
+    fn validate(&self, idx: usize) -> Result<(), String> {
+        if let Some(parent) = self.parent.filter(|parent| *parent >= idx) {
+            return Err(format!(
+                "\"parent\" must be a node listed before it (is {parent})"
+            ));
+        }
+        validate_size(self.size)?;
+        for object in &self.objects {
+            let object_error = |message: String| format!("object \"{}\" : {message}", object.name);
+            if object.velocity.is_some() {
+                let message = "moves with the node : give it the velocity".to_string();
+                return Err(object_error(message));
+            }
+            object.validate().map_err(object_error)?;
+        }
+        Ok(())
+    }
+}
+
+fn validate_size(size: f32) -> Result<(), String> {
+    if size.is_finite() && size > 0. {
+        Ok(())
+    } else {
+        Err(format!("\"size\" must be positive (is {size})"))
+    }
+}
+
+impl Shape {
+    fn validate(&self) -> Result<(), String> {
+        // Between "min" and MAX_DIVISIONS
+        let divisions = |name: &str, value: u32, min: u32| {
+            if value < min {
+                Err(format!("\"{name}\" must be at least {min} (is {value})"))
+            } else if value > MAX_DIVISIONS {
+                Err(format!(
+                    "\"{name}\" must be at most {MAX_DIVISIONS} (is {value})"
+                ))
+            } else {
+                Ok(())
+            }
+        };
+        let positive = |name: &str, value: f32| {
+            if value.is_finite() && value >= 0. {
+                Ok(())
+            } else {
+                Err(format!("\"{name}\" must be positive (is {value})"))
+            }
+        };
+        match *self {
+            Shape::Octahedron | Shape::Cube | Shape::Mesh { .. } => Ok(()),
+            Shape::UvSphere { rings, segments } => {
+                divisions("rings", rings, 2)?;
+                divisions("segments", segments, 3)
+            }
+            Shape::Icosphere { subdivisions } if subdivisions > MAX_SUBDIVISIONS => Err(format!(
+                "\"subdivisions\" must be at most {MAX_SUBDIVISIONS} (is {subdivisions})"
+            )),
+            Shape::Icosphere { .. } => Ok(()),
+            Shape::Cylinder { segments } | Shape::Cone { segments } => {
+                divisions("segments", segments, 3)
+            }
+            Shape::Torus {
+                rings,
+                segments,
+                thickness,
+            } => {
+                divisions("rings", rings, 3)?;
+                divisions("segments", segments, 3)?;
+                positive("thickness", thickness)
+            }
+            Shape::Plane { subdivisions } => divisions("subdivisions", subdivisions, 1),
+            Shape::Capsule {
+                rings,
+                segments,
+                length,
+            } => {
+                divisions("rings", rings, 1)?;
+                divisions("segments", segments, 3)?;
+                positive("length", length)
+            }
+        }
+    }
+
+    // At the origin, of unit size, "directory" : where Mesh paths are relative to
+    pub fn build(
+        &self,
+        directory: &Path,
+        files: &mut MeshFiles,
+    ) -> Result<Box<dyn ToMesh>, SceneError> {
+        let origin = origin();
+        Ok(match self {
+            Shape::Octahedron => Box::new(Octahedron::new_unoriented(origin, 1.)),
+            Shape::Cube => Box::new(Cube::new_unoriented(origin, 1.)),
+            Shape::UvSphere { rings, segments } => {
+                Box::new(UvSphere::new_unoriented(origin, 1., *rings, *segments))
+            }
+            Shape::Icosphere { subdivisions } => {
+                Box::new(Icosphere::new_unoriented(origin, 1., *subdivisions))
+            }
+            Shape::Cylinder { segments } => {
+                Box::new(Cylinder::new_unoriented(origin, 1., *segments))
+            }
+            Shape::Cone { segments } => Box::new(Cone::new_unoriented(origin, 1., *segments)),
+            Shape::Torus {
+                rings,
+                segments,
+                thickness,
+            } => Box::new(Torus::new_unoriented(
+                origin, 1., *rings, *segments, *thickness,
+            )),
+            Shape::Plane { subdivisions } => {
+                Box::new(Plane::new_unoriented(origin, 1., *subdivisions))
+            }
+            Shape::Capsule {
+                rings,
+                segments,
+                length,
+            } => Box::new(Capsule::new_unoriented(
+                origin, 1., *rings, *segments, *length,
+            )),
+            Shape::Mesh { path, index } => {
+                let path = directory.join(path);
+                let objects = files.load(&path)?;
+                let object = objects.get(*index).ok_or_else(|| {
+                    let message = format!("no object {index} (has {})", objects.len());
+                    SceneError::Mesh(path.clone(), message)
+                })?;
+                // Sharing the file's geometry and textures
+                Box::new(object.clone())
+            }
+        })
+    }
+}
+
+// Mesh files loaded while building scenes : objects of the same file share their geometry.
+// Kept across reloads : a file unchanged since (same modification time) isn't parsed again,
+// its objects keep their mesh keys (and the renderer their geometry)
+pub struct MeshFiles {
+    files: HashMap<PathBuf, MeshFile>,
+}
+
+struct MeshFile {
+    modified: Option<SystemTime>,
+    objects: Vec<MeshObject>,
+}
+
+impl MeshFiles {
+    pub fn new() -> MeshFiles {
+        MeshFiles {
+            files: HashMap::new(),
+        }
+    }
+
+    pub fn load(&mut self, path: &Path) -> Result<&[MeshObject], SceneError> {
+        let modified = fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok();
+        let unchanged = self
+            .files
+            .get(path)
+            .is_some_and(|file| file.modified == modified);
+        if !unchanged {
+            let objects = load_mesh_file(path)
+                .map_err(|message| SceneError::Mesh(path.to_path_buf(), message))?;
+            self.files
+                .insert(path.to_path_buf(), MeshFile { modified, objects });
+        }
+        Ok(&self.files[path].objects)
+    }
+}
+
+// Objects of an OBJ mesh (one, at the origin) or a glTF scene (.gltf, .glb)
+pub fn load_mesh_file(path: &Path) -> Result<Vec<MeshObject>, String> {
+    let extension = path.extension().and_then(|extension| extension.to_str());
+    match extension {
+        Some("gltf" | "glb") => load_gltf(path).map_err(|err| err.to_string()),
+        _ => load_obj(path)
+            .map(|mesh| vec![MeshObject::new_unoriented(origin(), 1., mesh)])
+            .map_err(|err| err.to_string()),
+    }
+}
+
+// Implicit "Some" : optional values are written as they are
+fn options() -> Options {
+    Options::default().with_default_extension(Extensions::IMPLICIT_SOME)
+}
+
+fn origin() -> Coord {
+    Coord::new(0., 0., 0.)
+}
+
+fn unit() -> f32 {
+    1.
+}
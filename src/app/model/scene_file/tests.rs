@@ -0,0 +1,308 @@
+use std::{
+    fs::File,
+    path::Path,
+    time::{Duration, SystemTime},
+};
+
+use glam::{Quat, Vec3};
+
+use crate::app::{
+    graphics_engine::ToMesh,
+    model::{
+        lighting::DirectionalLight,
+        space::{Coord, Orientation},
+        world::{Material, Velocity},
+        Camera, Lighting, Model, SHOWCASE,
+    },
+    temp_dir::TempDir,
+};
+
+use super::{
+    MeshFiles, NodeDescription, ObjectDescription, SceneDescription, SceneError, Shape,
+    SCENE_VERSION,
+};
+
+fn scene() -> SceneDescription {
+    SceneDescription {
+        version: SCENE_VERSION,
+        camera: Camera::new(Vec3::new(1., 2., 3.), Vec3::ZERO),
+        lighting: Lighting {
+            ambient: Vec3::splat(0.1),
+            lights: vec![DirectionalLight {
+                direction: Vec3::Y,
+                color: Vec3::new(1., 0.5, 0.),
+            }],
+        },
+        objects: vec![
+            ObjectDescription {
+                name: "spinning torus".to_string(),
+                shape: Shape::Torus {
+                    rings: 8,
+                    segments: 6,
+                    thickness: 0.25,
+                },
+                position: Coord::new(1., 0., -1.),
+                orientation: Orientation::from_quat(Quat::from_rotation_x(0.5)),
+                size: 0.5,
+                material: Some(Material {
+                    color: Vec3::new(1., 0., 0.),
+                }),
+                velocity: Some(Velocity::spinning(Vec3::Z)),
+            },
+            ObjectDescription {
+                name: "cube".to_string(),
+                shape: Shape::Cube,
+                position: Coord::new(0., 0., 0.),
+                orientation: Orientation::initial(),
+                size: 1.,
+                material: None,
+                velocity: None,
+            },
+        ],
+        nodes: vec![
+            NodeDescription {
+                name: "planet".to_string(),
+                parent: None,
+                position: Coord::new(0., 1., 0.),
+                orientation: Orientation::initial(),
+                size: 1.,
+                velocity: Some(Velocity::spinning(Vec3::Y)),
+                objects: vec![ObjectDescription {
+                    name: "planet".to_string(),
+                    shape: Shape::Icosphere { subdivisions: 2 },
+                    position: Coord::new(0., 0., 0.),
+                    orientation: Orientation::initial(),
+                    size: 0.5,
+                    material: Some(Material {
+                        color: Vec3::new(0., 0., 1.),
+                    }),
+                    velocity: None,
+                }],
+            },
+            NodeDescription {
+                name: "moon".to_string(),
+                parent: Some(0),
+                position: Coord::new(2., 0., 0.),
+                orientation: Orientation::from_quat(Quat::from_rotation_z(0.5)),
+                size: 0.5,
+                velocity: None,
+                objects: vec![ObjectDescription {
+                    name: "moon".to_string(),
+                    shape: Shape::Cube,
+                    position: Coord::new(0., 0.5, 0.),
+                    orientation: Orientation::initial(),
+                    size: 0.1,
+                    material: None,
+                    velocity: None,
+                }],
+            },
+        ],
+    }
+}
+
+// Message of the error parsing "text"
+fn parse_error(text: &str) -> String {
+    SceneDescription::parse(text)
+        .and_then(|scene| scene.validate())
+        .expect_err("Malformed scene accepted.")
+        .to_string()
+}
+
+#[test]
+fn text_round_trip() {
+    let scene = scene();
+    let text = scene.to_text().unwrap();
+    assert_eq!(SceneDescription::parse(&text).unwrap(), scene);
+}
+
+// Description -> model -> description
+#[test]
+fn model_round_trip() {
+    let scene = scene();
+    let model = Model::from_scene(&scene, Path::new(""), &mut MeshFiles::new()).unwrap();
+    let saved = model.to_scene(Path::new(""));
+    assert_eq!(saved.camera, scene.camera);
+    assert_eq!(saved.lighting, scene.lighting);
+    assert_eq!(saved.objects.len(), scene.objects.len());
+    for (saved, object) in saved.objects.iter().zip(&scene.objects) {
+        assert_eq!(saved.name, object.name);
+        assert_eq!(saved.shape, object.shape);
+        assert!(saved
+            .position
+            .to_vec3()
+            .abs_diff_eq(object.position.to_vec3(), 1e-6));
+        assert!(saved
+            .orientation
+            .to_quat()
+            .abs_diff_eq(object.orientation.to_quat(), 1e-6));
+        assert!((saved.size - object.size).abs() < 1e-6);
+        assert_eq!(saved.material, object.material);
+        assert_eq!(saved.velocity, object.velocity);
+    }
+    // The hierarchy too
+    assert_eq!(saved.nodes.len(), scene.nodes.len());
+    for (saved, node) in saved.nodes.iter().zip(&scene.nodes) {
+        assert_eq!(saved.name, node.name);
+        assert_eq!(saved.parent, node.parent);
+        assert!(saved
+            .position
+            .to_vec3()
+            .abs_diff_eq(node.position.to_vec3(), 1e-6));
+        assert!(saved
+            .orientation
+            .to_quat()
+            .abs_diff_eq(node.orientation.to_quat(), 1e-6));
+        assert!((saved.size - node.size).abs() < 1e-6);
+        assert_eq!(saved.velocity, node.velocity);
+        assert_eq!(saved.objects, node.objects);
+    }
+}
+
+#[test]
+fn showcase_is_valid() {
+    let showcase = SceneDescription::parse(SHOWCASE).unwrap();
+    showcase.validate().unwrap();
+    assert!(!showcase.objects.is_empty());
+    assert!(!showcase.nodes.is_empty());
+}
+
+#[test]
+fn optional_fields_have_defaults() {
+    let text = "(
+        version: 1,
+        camera: (eye: (0, 0, 2), center: (0, 0, 0)),
+        objects: [(name: \"ball\", shape: Icosphere(subdivisions: 1))],
+    )";
+    let scene = SceneDescription::parse(text).unwrap();
+    assert_eq!(scene.lighting, Lighting::default_lighting());
+    assert_eq!(scene.camera.up, Vec3::Y);
+    let ball = &scene.objects[0];
+    assert_eq!(ball.position, Coord::new(0., 0., 0.));
+    assert_eq!(ball.orientation, Orientation::initial());
+    assert_eq!(ball.size, 1.);
+    assert_eq!((ball.material, ball.velocity), (None, None));
+}
+
+#[test]
+fn versions() {
+    let missing = "(camera: (eye: (0, 0, 2), center: (0, 0, 0)))";
+    assert!(matches!(
+        SceneDescription::parse(missing),
+        Err(SceneError::MissingVersion)
+    ));
+    // Only the version is read from newer files : they may not make sense anymore
+    let newer = format!(
+        "(version: {}, camera: \"somewhere else\")",
+        SCENE_VERSION + 1
+    );
+    assert!(matches!(
+        SceneDescription::parse(&newer),
+        Err(SceneError::UnsupportedVersion(version)) if version == SCENE_VERSION + 1
+    ));
+    let zero = "(version: 0, camera: (eye: (0, 0, 2), center: (0, 0, 0)))";
+    assert!(matches!(
+        SceneDescription::parse(zero),
+        Err(SceneError::UnsupportedVersion(0))
+    ));
+}
+
+// Errors tell where and what
+#[test]
+fn malformed_scenes_are_explained() {
+    let unknown_shape = "(
+        version: 1,
+        camera: (eye: (0, 0, 2), center: (0, 0, 0)),
+        objects: [(name: \"thing\", shape: Teapot)],
+    )";
+    let message = parse_error(unknown_shape);
+    assert!(message.starts_with("line 4"), "{message}");
+    assert!(message.contains("Teapot"), "{message}");
+
+    let missing_field = "(version: 1, objects: [])";
+    assert!(parse_error(missing_field).contains("camera"));
+
+    let negative_size = "(
+        version: 1,
+        camera: (eye: (0, 0, 2), center: (0, 0, 0)),
+        objects: [(name: \"flat\", shape: Cube, size: -1)],
+    )";
+    assert_eq!(
+        parse_error(negative_size),
+        "object \"flat\" : \"size\" must be positive (is -1)"
+    );
+
+    let coarse_cone = "(
+        version: 1,
+        camera: (eye: (0, 0, 2), center: (0, 0, 0)),
+        objects: [(name: \"cone\", shape: Cone(segments: 2))],
+    )";
+    assert_eq!(
+        parse_error(coarse_cone),
+        "object \"cone\" : \"segments\" must be at least 3 (is 2)"
+    );
+
+    let huge_plane = "(
+        version: 1,
+        camera: (eye: (0, 0, 2), center: (0, 0, 0)),
+        objects: [(name: \"floor\", shape: Plane(subdivisions: 4294967295))],
+    )";
+    assert_eq!(
+        parse_error(huge_plane),
+        "object \"floor\" : \"subdivisions\" must be at most 1024 (is 4294967295)"
+    );
+
+    let later_parent = "(
+        version: 1,
+        camera: (eye: (0, 0, 2), center: (0, 0, 0)),
+        nodes: [(name: \"moon\", parent: 1), (name: \"planet\")],
+    )";
+    assert_eq!(
+        parse_error(later_parent),
+        "node \"moon\" : \"parent\" must be a node listed before it (is 1)"
+    );
+
+    let moving_node_object = "(
+        version: 1,
+        camera: (eye: (0, 0, 2), center: (0, 0, 0)),
+        nodes: [(
+            name: \"planet\",
+            objects: [(name: \"ball\", shape: Cube, velocity: (linear: (1, 0, 0)))],
+        )],
+    )";
+    assert_eq!(
+        parse_error(moving_node_object),
+        "node \"planet\" : object \"ball\" : moves with the node : give it the velocity"
+    );
+}
+
+#[test]
+fn missing_mesh_files_are_reported() {
+    let mut scene = scene();
+    scene.objects[1].shape = Shape::Mesh {
+        path: "missing.obj".into(),
+        index: 0,
+    };
+    let err = Model::from_scene(&scene, Path::new("nowhere"), &mut MeshFiles::new())
+        .err()
+        .unwrap();
+    assert!(matches!(err, SceneError::Mesh(path, _) if path == Path::new("nowhere/missing.obj")));
+}
+
+// Reloading keeps the mesh keys of unchanged files, parses changed ones again
+#[test]
+fn mesh_files_are_reused_until_changed() {
+    let directory = TempDir::new("scene_mesh_files");
+    let path = directory.write("triangle.obj", "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n");
+    let mut files = MeshFiles::new();
+    let key = |files: &mut MeshFiles| files.load(&path).unwrap()[0].mesh_key();
+    let first = key(&mut files);
+    assert_eq!(key(&mut files), first);
+
+    File::options()
+        .write(true)
+        .open(&path)
+        .unwrap()
+        .set_modified(SystemTime::now() + Duration::from_secs(10))
+        .unwrap();
+    assert_ne!(key(&mut files), first);
+}
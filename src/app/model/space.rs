@@ -1,5 +1,9 @@
 use glam::{Quat, Vec3};
+use serde::{Deserialize, Serialize};
 
+// Written as its vector : (x, y, z)
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(transparent)]
 pub struct Coord {
     pub coord: Vec3,
 }
@@ -16,22 +20,49 @@ impl Coord {
     }
 }
 
+// Written as its quaternion : (x, y, z, w), normalized when read
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(from = "Quat", into = "Quat")]
 pub struct Orientation {
     orientation: Quat,
 }
 
 impl Orientation {
-    pub fn initial() -> Orientation {
+    pub const fn initial() -> Orientation {
         Orientation {
             orientation: Quat::IDENTITY,
         }
     }
 
-    pub fn to_quat(&self) -> Quat {
+    pub fn from_quat(quat: Quat) -> Orientation {
+        Orientation {
+            orientation: quat.normalize(),
+        }
+    }
+
+    pub fn to_quat(self) -> Quat {
         self.orientation
     }
 
     pub fn rotate(&mut self, quat: Quat) {
         self.orientation *= quat;
     }
+
+    // Turn for "dt" seconds at "angular_velocity" (axis scaled by radians per second, local)
+    pub fn spin(&mut self, angular_velocity: Vec3, dt: f32) {
+        self.rotate(Quat::from_scaled_axis(angular_velocity * dt));
+        self.orientation = self.orientation.normalize();
+    }
+}
+
+impl From<Quat> for Orientation {
+    fn from(quat: Quat) -> Orientation {
+        Orientation::from_quat(quat)
+    }
+}
+
+impl From<Orientation> for Quat {
+    fn from(orientation: Orientation) -> Quat {
+        orientation.to_quat()
+    }
 }
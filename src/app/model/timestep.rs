@@ -0,0 +1,70 @@
+#[cfg(test)]
+mod tests;
+
+use std::time::{Duration, Instant};
+
+use glam::Mat4;
+
+// Simulation ticks of a fixed duration, whatever the frame rate : elapsed time accumulates
+// and is consumed tick by tick. The leftover tells how far rendering is between two ticks.
+pub struct FixedTimestep {
+    tick: Duration,
+    // Ticks run at most per advance, time beyond is dropped (the simulation slows down
+    // rather than spiraling when ticks cost more than they simulate)
+    max_catch_up: u32,
+    accumulator: Duration,
+    last_advance: Instant,
+}
+
+impl FixedTimestep {
+    // "tick_rate" : ticks per second
+    pub fn new(tick_rate: f64, max_catch_up: u32) -> FixedTimestep {
+        FixedTimestep {
+            tick: Duration::from_secs_f64(1. / tick_rate),
+            max_catch_up,
+            accumulator: Duration::ZERO,
+            last_advance: Instant::now(),
+        }
+    }
+
+    // Ticks to run for the time elapsed since the previous advance
+    pub fn advance(&mut self) -> u32 {
+        let now = Instant::now();
+        let elapsed = now - self.last_advance;
+        self.last_advance = now;
+        self.advance_by(elapsed)
+    }
+
+    pub fn advance_by(&mut self, elapsed: Duration) -> u32 {
+        self.accumulator += elapsed;
+        let ticks = (self.accumulator.as_nanos() / self.tick.as_nanos()) as u32;
+        if ticks > self.max_catch_up {
+            self.accumulator = Duration::ZERO;
+            return self.max_catch_up;
+        }
+        self.accumulator -= self.tick * ticks;
+        ticks
+    }
+
+    // Seconds simulated by a tick
+    pub fn dt(&self) -> f32 {
+        self.tick.as_secs_f32()
+    }
+
+    // How far the time is from the last tick to the next one, in [0, 1)
+    pub fn alpha(&self) -> f32 {
+        self.accumulator.as_secs_f32() / self.tick.as_secs_f32()
+    }
+}
+
+// Transform "alpha" of the way from "previous" to "current" (rotations are slerped)
+pub fn interpolate(previous: Mat4, current: Mat4, alpha: f32) -> Mat4 {
+    let (previous_scale, previous_rotation, previous_translation) =
+        previous.to_scale_rotation_translation();
+    let (scale, rotation, translation) = current.to_scale_rotation_translation();
+    Mat4::from_scale_rotation_translation(
+        previous_scale.lerp(scale, alpha),
+        previous_rotation.slerp(rotation, alpha),
+        previous_translation.lerp(translation, alpha),
+    )
+}
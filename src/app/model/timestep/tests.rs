@@ -0,0 +1,60 @@
+use std::time::Duration;
+
+use glam::{Mat4, Quat, Vec3};
+
+use super::{interpolate, FixedTimestep};
+
+fn millis(millis: u64) -> Duration {
+    Duration::from_millis(millis)
+}
+
+#[test]
+fn ticks_follow_elapsed_time() {
+    let mut timestep = FixedTimestep::new(100., 10);
+    assert_eq!(timestep.advance_by(millis(5)), 0);
+    assert!((timestep.alpha() - 0.5).abs() < 1e-4);
+    // The leftover counts
+    assert_eq!(timestep.advance_by(millis(25)), 3);
+    assert!(timestep.alpha().abs() < 1e-4);
+    assert!((timestep.dt() - 0.01).abs() < 1e-6);
+}
+
+// Ticks only depend on the total time (whole ticks in it), however it's split in frames
+#[test]
+fn ticks_are_independent_of_frame_rate() {
+    // About 3 s of "frame" ms frames
+    let ticks_for = |frame: u64| {
+        let mut timestep = FixedTimestep::new(50., 100);
+        (0..(3000 / frame))
+            .map(|_| timestep.advance_by(millis(frame)))
+            .sum::<u32>()
+    };
+    assert_eq!(ticks_for(1), 150);
+    assert_eq!(ticks_for(50), 150);
+    assert_eq!(ticks_for(7), 149); // 2996 ms
+    assert_eq!(ticks_for(16), 149); // 2992 ms
+}
+
+#[test]
+fn catch_up_is_bounded() {
+    let mut timestep = FixedTimestep::new(100., 5);
+    assert_eq!(timestep.advance_by(Duration::from_secs(2)), 5);
+    // Dropped, not postponed
+    assert_eq!(timestep.advance_by(millis(0)), 0);
+}
+
+#[test]
+fn interpolates_between_ticks() {
+    let previous = Mat4::from_scale_rotation_translation(Vec3::ONE, Quat::IDENTITY, Vec3::ZERO);
+    let current = Mat4::from_scale_rotation_translation(
+        Vec3::splat(3.),
+        Quat::from_rotation_y(1.),
+        Vec3::new(2., 0., 0.),
+    );
+    let halfway = interpolate(previous, current, 0.5);
+    let (scale, rotation, translation) = halfway.to_scale_rotation_translation();
+    assert!(scale.abs_diff_eq(Vec3::splat(2.), 1e-4));
+    assert!(rotation.abs_diff_eq(Quat::from_rotation_y(0.5), 1e-4));
+    assert!(translation.abs_diff_eq(Vec3::new(1., 0., 0.), 1e-4));
+    assert!(interpolate(previous, current, 1.).abs_diff_eq(current, 1e-4));
+}
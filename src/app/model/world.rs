@@ -0,0 +1,100 @@
+mod components;
+pub mod systems;
+#[cfg(test)]
+mod tests;
+
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+};
+
+pub use components::{Material, Name, Renderable, Velocity};
+
+// An entity of a world : the index of its components in their stores
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Entity {
+    index: u32,
+}
+
+// What a system does to the world each tick ("dt" in seconds)
+pub type System = fn(&mut World, f32);
+
+// Components of one type, by entity index
+type Store<C> = Vec<Option<C>>;
+
+// Entities with any components : a new kind of object is a new combination of components,
+// a new behaviour is a system iterating over the entities having the components it needs
+pub struct World {
+    entity_count: u32,
+    // Store<C> of each component type, keyed by its type id
+    stores: HashMap<TypeId, Box<dyn Any>>,
+}
+
+impl World {
+    pub fn new() -> World {
+        World {
+            entity_count: 0,
+            stores: HashMap::new(),
+        }
+    }
+
+    // An entity without components
+    pub fn spawn(&mut self) -> Entity {
+        self.entity_count += 1;
+        Entity {
+            index: self.entity_count - 1,
+        }
+    }
+
+    // Adds "component" to "entity", replacing the one of the same type
+    pub fn insert<C: 'static>(&mut self, entity: Entity, component: C) {
+        let store = self.store_mut::<C>();
+        let index = entity.index as usize;
+        if store.len() <= index {
+            store.resize_with(index + 1, || None);
+        }
+        store[index] = Some(component);
+    }
+
+    pub fn get<C: 'static>(&self, entity: Entity) -> Option<&C> {
+        self.store::<C>()?.get(entity.index as usize)?.as_ref()
+    }
+
+    pub fn get_mut<C: 'static>(&mut self, entity: Entity) -> Option<&mut C> {
+        self.store_mut::<C>()
+            .get_mut(entity.index as usize)?
+            .as_mut()
+    }
+
+    // Entities having a "C", with it
+    pub fn query<C: 'static>(&self) -> impl Iterator<Item = (Entity, &C)> {
+        let components = self.store::<C>().map_or(&[][..], Vec::as_slice);
+        components
+            .iter()
+            .enumerate()
+            .filter_map(|(index, component)| {
+                let entity = Entity {
+                    index: index as u32,
+                };
+                Some((entity, component.as_ref()?))
+            })
+    }
+
+    // Entities having both a "A" and a "B", with them
+    pub fn query2<A: 'static, B: 'static>(&self) -> impl Iterator<Item = (Entity, &A, &B)> {
+        self.query::<A>()
+            .filter_map(|(entity, a)| Some((entity, a, self.get::<B>(entity)?)))
+    }
+
+    fn store<C: 'static>(&self) -> Option<&Store<C>> {
+        self.stores.get(&TypeId::of::<C>())?.downcast_ref()
+    }
+
+    fn store_mut<C: 'static>(&mut self) -> &mut Store<C> {
+        self.stores
+            .entry(TypeId::of::<C>())
+            .or_insert_with(|| Box::new(Store::<C>::new()))
+            .downcast_mut()
+            .expect("Failed to downcast component store.")
+    }
+}
@@ -0,0 +1,59 @@
+use glam::Vec3;
+use serde::{Deserialize, Serialize};
+
+use crate::app::{graphics_engine::ToMesh, model::scene::Transform};
+
+// Transform (scene::Transform) places an entity in the world
+
+// For printing and looking entities up
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Name(pub String);
+
+// Speeds applied to the entity's Transform by "systems::integrate_velocities"
+// (and to scene nodes' local transforms by "SceneGraph::tick")
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Velocity {
+    // Units per second
+    #[serde(default)]
+    pub linear: Vec3,
+    // Axis scaled by radians per second (local)
+    #[serde(default)]
+    pub angular: Vec3,
+}
+
+impl Velocity {
+    #[cfg(test)]
+    pub fn spinning(angular: Vec3) -> Velocity {
+        Velocity {
+            linear: Vec3::ZERO,
+            angular,
+        }
+    }
+
+    // Moves and turns "transform" for "dt" seconds
+    pub fn apply(&self, transform: &mut Transform, dt: f32) {
+        transform.translation += self.linear * dt;
+        transform.rotation.spin(self.angular, dt);
+    }
+}
+
+// Color multiplying the colors of the entity's Renderable (its shape's material), drawn as
+// such : tinted renderables share their shape's geometry. Given to the Renderable by
+// "systems::apply_materials"
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Material {
+    pub color: Vec3,
+}
+
+// What the entity looks like : a shape (with its own transform, relative to the entity's)
+pub struct Renderable {
+    pub shape: Box<dyn ToMesh>,
+    // The entity's Material (set by "systems::apply_materials")
+    pub tint: Option<Material>,
+}
+
+impl Renderable {
+    pub fn new(shape: Box<dyn ToMesh>) -> Renderable {
+        Renderable { shape, tint: None }
+    }
+}
@@ -0,0 +1,43 @@
+use crate::app::model::{object::Placed, scene::Transform};
+
+use super::{Material, Renderable, System, Velocity, World};
+
+// Run each tick, in this order
+pub const DEFAULT_SYSTEMS: [System; 2] = [integrate_velocities, apply_materials];
+
+// Moves and turns entities having a Velocity and a Transform
+pub fn integrate_velocities(world: &mut World, dt: f32) {
+    let velocities: Vec<_> = world
+        .query::<Velocity>()
+        .map(|(entity, velocity)| (entity, *velocity))
+        .collect();
+    for (entity, velocity) in velocities {
+        if let Some(transform) = world.get_mut::<Transform>(entity) {
+            velocity.apply(transform, dt);
+        }
+    }
+}
+
+// Gives renderables their entity's Material (none for those without one)
+pub fn apply_materials(world: &mut World, _dt: f32) {
+    let materials: Vec<_> = world
+        .query::<Renderable>()
+        .map(|(entity, _)| (entity, world.get::<Material>(entity).copied()))
+        .collect();
+    for (entity, material) in materials {
+        world
+            .get_mut::<Renderable>(entity)
+            .expect("Failed to get a queried renderable.")
+            .tint = material;
+    }
+}
+
+// Renderables placed by their entity's Transform (their own transform composed with it)
+pub fn renderables(world: &World) -> impl Iterator<Item = Placed<'_>> {
+    world
+        .query2::<Renderable, Transform>()
+        .map(|(_, renderable, transform)| Placed {
+            object: renderable,
+            transform: transform.matrix() * renderable.shape.transform(),
+        })
+}
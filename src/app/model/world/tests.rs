@@ -0,0 +1,126 @@
+use glam::{Quat, Vec3};
+
+use crate::app::{
+    graphics_engine::ToMesh,
+    model::{
+        object::Cube,
+        scene::Transform,
+        space::{Coord, Orientation},
+    },
+};
+
+use super::{systems, Material, Name, Renderable, Velocity, World};
+
+fn cube() -> Renderable {
+    Renderable::new(Box::new(Cube::new_unoriented(Coord::new(0., 0., 0.), 0.5)))
+}
+
+#[test]
+fn components_by_entity() {
+    let mut world = World::new();
+    let a = world.spawn();
+    let b = world.spawn();
+    world.insert(a, Name("a".to_string()));
+    world.insert(b, Name("b".to_string()));
+    world.insert(b, Velocity::spinning(Vec3::X));
+
+    assert_eq!(world.get::<Name>(a), Some(&Name("a".to_string())));
+    assert!(world.get::<Velocity>(a).is_none());
+    // Replacing
+    world.insert(a, Name("c".to_string()));
+    let names: Vec<_> = world
+        .query::<Name>()
+        .map(|(_, name)| name.0.clone())
+        .collect();
+    assert_eq!(names, ["c", "b"]);
+
+    let moving: Vec<_> = world
+        .query2::<Name, Velocity>()
+        .map(|(entity, _, _)| entity)
+        .collect();
+    assert_eq!(moving, [b]);
+}
+
+#[test]
+fn velocities_move_transforms() {
+    let mut world = World::new();
+    let moving = world.spawn();
+    world.insert(
+        moving,
+        Transform::new(Vec3::ZERO, Orientation::initial(), 1.),
+    );
+    world.insert(
+        moving,
+        Velocity {
+            linear: Vec3::new(1., 0., 0.),
+            angular: Vec3::new(0., 2., 0.),
+        },
+    );
+    // No transform to move
+    let loose = world.spawn();
+    world.insert(loose, Velocity::spinning(Vec3::X));
+
+    systems::integrate_velocities(&mut world, 0.5);
+    let transform = world.get::<Transform>(moving).unwrap();
+    assert!(transform
+        .translation
+        .abs_diff_eq(Vec3::new(0.5, 0., 0.), 1e-6));
+    assert!(transform
+        .rotation
+        .to_quat()
+        .abs_diff_eq(Quat::from_rotation_y(1.), 1e-6));
+}
+
+// Material colors tint the shape's material : tinted renderables keep sharing its geometry
+#[test]
+fn materials_tint_renderables() {
+    let mut world = World::new();
+    let tinted = world.spawn();
+    world.insert(tinted, Transform::new(Vec3::Y, Orientation::initial(), 1.));
+    world.insert(tinted, cube());
+    let plain = world.spawn();
+    world.insert(
+        plain,
+        Transform::new(Vec3::ZERO, Orientation::initial(), 1.),
+    );
+    world.insert(plain, cube());
+
+    let red = Material {
+        color: Vec3::new(1., 0., 0.),
+    };
+    world.insert(tinted, red);
+    systems::apply_materials(&mut world, 0.);
+    let renderable = world.get::<Renderable>(tinted).unwrap();
+    assert_eq!(renderable.material().base_color, red.color);
+    assert_eq!(
+        renderable.mesh_key(),
+        world.get::<Renderable>(plain).unwrap().mesh_key()
+    );
+    assert_eq!(
+        world
+            .get::<Renderable>(plain)
+            .unwrap()
+            .material()
+            .base_color,
+        Vec3::ONE
+    );
+}
+
+// Only entities with both a renderable and a transform are drawn, placed by the transform
+#[test]
+fn renderables_are_placed_by_their_entity() {
+    let mut world = World::new();
+    let drawn = world.spawn();
+    world.insert(
+        drawn,
+        Transform::new(Vec3::new(0., 2., 0.), Orientation::initial(), 1.),
+    );
+    world.insert(drawn, cube());
+    let unplaced = world.spawn();
+    world.insert(unplaced, cube());
+
+    let placed: Vec<_> = systems::renderables(&world).collect();
+    assert_eq!(placed.len(), 1);
+    let corner = placed[0].transform.transform_point3(Vec3::ONE);
+    assert!(corner.abs_diff_eq(Vec3::new(0.5, 2.5, 0.5), 1e-6));
+}
@@ -1,5 +1,7 @@
 mod app;
 
+use std::path::PathBuf;
+
 use app::App;
 use winit::{
     event_loop::{ControlFlow, EventLoop},
@@ -7,10 +9,48 @@ use winit::{
 };
 
 fn main() {
+    // Arguments are a scene file (.ron, instead of the showcase) and OBJ and glTF files to view,
+    // after "--headless <output.png>" to render them once without window
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let headless_output = if args.first().map(String::as_str) == Some("--headless") {
+        let output = PathBuf::from(
+            args.get(1)
+                .expect("Usage : --headless <output.png> [scene.ron] [files]"),
+        );
+        args.drain(..2);
+        Some(output)
+    } else {
+        None
+    };
+    let (scene_paths, file_paths): (Vec<PathBuf>, Vec<PathBuf>) = args
+        .into_iter()
+        .map(PathBuf::from)
+        .partition(|path| path.extension().is_some_and(|extension| extension == "ron"));
+    if scene_paths.len() > 1 {
+        eprintln!("Only one scene file can be given.");
+        std::process::exit(1);
+    }
+    let scene_path = scene_paths.into_iter().next();
+
+    if let Some(output) = headless_output {
+        if let Err(err) = app::render_headless(scene_path.as_deref(), &file_paths, &output) {
+            eprintln!("Failed to save the render : {err}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
     // Create event_loop and app
     let event_loop = EventLoop::new().expect("Failed to create event loop");
     let raw_display_handle: RawDisplayHandle = get_rdh_from_event_loop(&event_loop);
-    let mut app = App::new(raw_display_handle);
+    let mut app = match App::new(raw_display_handle, scene_path.clone(), &file_paths) {
+        Ok(app) => app,
+        Err(err) => {
+            let path = scene_path.unwrap_or_default();
+            eprintln!("Failed to load the scene {} : {err}", path.display());
+            std::process::exit(1);
+        }
+    };
 
     // Settings
     event_loop.set_control_flow(ControlFlow::Poll);